@@ -0,0 +1,50 @@
+//! Times reading every block of a full chunk with get_block, run with `cargo bench`. For comparison the same loop is
+//! timed again with a copy of the section compound made for every block, which is what get_section used to do.
+
+use nbt::Value;
+use simple_anvil::{block::Block, region::RegionBuilder};
+
+use std::{hint::black_box, time::Instant};
+
+fn main() {
+    let names = ["minecraft:stone", "minecraft:dirt", "minecraft:granite", "minecraft:andesite", "minecraft:diorite"];
+    let mut builder = RegionBuilder::new("r.0.0.mca".into());
+    for y in -64..320i32 {
+        for z in 0..16 {
+            for x in 0..16 {
+                let name = names[(x + y * 3 + z * 7).rem_euclid(names.len() as i32) as usize];
+                builder.set_block(0, 0, x, y, z, &Block::from_name(name.into(), None, None, None));
+            }
+        }
+    }
+    let region = builder.build();
+    let chunk = region.get_chunk(0, 0).unwrap();
+    let sections = match chunk.data.get("sections") {
+        Some(Value::List(sections)) => sections.clone(),
+        _ => panic!("the chunk has no sections"),
+    };
+
+    let start = Instant::now();
+    for y in -64..320 {
+        for z in 0..16 {
+            for x in 0..16 {
+                black_box(chunk.get_block(x, y, z).unwrap());
+            }
+        }
+    }
+    let borrowed = start.elapsed();
+
+    let start = Instant::now();
+    for y in -64..320 {
+        for z in 0..16 {
+            for x in 0..16 {
+                black_box(sections[((y + 64) / 16) as usize].clone());
+                black_box(chunk.get_block(x, y, z).unwrap());
+            }
+        }
+    }
+    let cloned = start.elapsed();
+
+    println!("get_block over 98304 blocks: {:?}", borrowed);
+    println!("get_block over 98304 blocks, copying the section each time: {:?}", cloned);
+}
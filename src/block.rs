@@ -1,11 +1,16 @@
-use core::{fmt, panic};
+use core::fmt;
 
 use nbt::Value;
 
+use crate::chunk::ChunkError;
+
+use std::collections::HashMap;
+
 /// A Minecraft block. This struct does not store any data about the location because
 /// to get a block one must use x, y, and z coordinates on a Chunk and thus would
-/// already have the location data.
-#[derive(Debug, Eq, PartialEq)]
+/// already have the location data. With the `serde` feature blocks can be serialized and deserialized.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block {
     namespace: String,
     /// The general name of a block, ie. 'stone'
@@ -14,8 +19,8 @@ pub struct Block {
     pub coords: Option<(i32, i32, i32)>,
     /// Any properties that a block might have.
     pub properties: Option<Vec<(String, String)>>,
-    /// The biome that the block is a part of.
-    pub biome: String
+    /// The biome that the block is a part of, None if it is not known.
+    pub biome: Option<String>
 }
 
 impl Block {
@@ -34,7 +39,7 @@ impl Block {
     /// let block = Block::new("minecraft".into(), Some("stone".into()));
     /// println!("{}", block.id);
     /// ```
-    pub fn new(namespace: String, block_id: Option<String>, coords: Option<(i32, i32, i32)>, properties: Option<Vec<(String, String)>>, biome: String) -> Block {
+    pub fn new(namespace: String, block_id: Option<String>, coords: Option<(i32, i32, i32)>, properties: Option<Vec<(String, String)>>, biome: Option<String>) -> Block {
         match block_id {
             Some(id) => return Block { namespace, id, coords, properties, biome },
             None => {
@@ -58,14 +63,83 @@ impl Block {
     /// let block = Block::new("minecraft".into(), Some("stone".into()));
     /// println!("{}", block.name());
     /// ```
-    pub fn name(self) -> String {
-        let mut name = self.namespace;
+    pub fn name(&self) -> String {
+        let mut name = self.namespace.clone();
         name += ":";
         name += self.id.as_str();
         return name;
     }
 
-    /// Returns a Block from a name
+    /// An alias of name, returning the full name of the block, ie. 'minecraft:stone'. It was added while name still
+    /// took the block by value, now that name borrows the block either can be used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use simple_anvil::block::Block;
+    /// let block = Block::from_name("minecraft:stone".into(), Some((1, 2, 3)), None, None);
+    /// assert_eq!(block.full_name(), "minecraft:stone");
+    /// assert_eq!(block.full_name(), block.to_string());
+    /// ```
+    pub fn full_name(&self) -> String {
+        return self.name();
+    }
+
+    /// Returns the properties of the block as a map from the property name to its value. The map is empty if the
+    /// block has no properties.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use simple_anvil::block::Block;
+    /// let properties = Some(vec![("facing".to_string(), "north".to_string())]);
+    /// let block = Block::from_name("minecraft:oak_stairs".into(), None, properties, None);
+    /// assert_eq!(block.properties_map()["facing"], "north");
+    /// ```
+    pub fn properties_map(&self) -> HashMap<String, String> {
+        return match &self.properties {
+            Some(p) => p.iter().cloned().collect(),
+            None => HashMap::new(),
+        };
+    }
+
+    /// Returns the value of one of the block's properties, None if the block does not have the property.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the property, ie. 'facing'
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use simple_anvil::block::Block;
+    /// let properties = Some(vec![("facing".to_string(), "north".to_string())]);
+    /// let block = Block::from_name("minecraft:oak_stairs".into(), None, properties, None);
+    /// assert_eq!(block.property("facing"), Some("north"));
+    /// assert_eq!(block.property("half"), None);
+    /// ```
+    pub fn property(&self, key: &str) -> Option<&str> {
+        return self.properties.as_ref()?.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+    }
+
+    /// Returns true if the block is waterlogged, which is only the case when its waterlogged property is 'true'.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use simple_anvil::block::Block;
+    /// let properties = Some(vec![("waterlogged".to_string(), "true".to_string())]);
+    /// let fence = Block::from_name("minecraft:oak_fence".into(), None, properties, None);
+    /// let stone = Block::from_name("minecraft:stone".into(), None, None, None);
+    /// assert!(fence.is_waterlogged());
+    /// assert!(!stone.is_waterlogged());
+    /// ```
+    pub fn is_waterlogged(&self) -> bool {
+        return self.property("waterlogged") == Some("true");
+    }
+
+    /// Returns a Block from a name. The name is split at the first colon, so everything after it is the id. A name
+    /// without a namespace, ie. 'stone', is given the 'minecraft' namespace. Use parse to reject empty names.
     ///
     /// # Arguments
     ///
@@ -76,39 +150,189 @@ impl Block {
     ///
     /// ```rust
     /// use simple_anvil::block::Block;
-    /// let block = Block::from_name("minecraft:stone".into());
+    /// let block = Block::from_name("minecraft:stone".into(), None, None, None);
+    /// assert_eq!(block, Block::from_name("stone".into(), None, None, None));
     /// println!("{}", block.id);
+    ///
+    /// let odd = Block::from_name("minecraft:foo:bar".into(), None, None, None);
+    /// assert_eq!(odd.id, "foo:bar");
+    /// assert_eq!(odd.name(), "minecraft:foo:bar");
     /// ```
-    pub fn from_name(name: String, coords: Option<(i32, i32, i32)>, properties: Option<Vec<(String, String)>>, biome: String) -> Block {
-        let temp: Vec<&str> = name.split(":").collect();
+    pub fn from_name(name: String, coords: Option<(i32, i32, i32)>, properties: Option<Vec<(String, String)>>, biome: Option<String>) -> Block {
+        let (namespace, id) = match name.split_once(':') {
+            Some((namespace, id)) => (namespace.to_owned(), id.to_owned()),
+            None => (String::from("minecraft"), name),
+        };
         return Block {
-            namespace: temp[0].to_owned(),
-            id: temp[1].to_owned(),
+            namespace,
+            id,
             coords,
             properties,
             biome
         };
     }
 
-    /// Returns a block from a Chunk palette value
+    /// Returns a Block from a name without any coordinates, properties, or biome. None is returned if the name,
+    /// or either side of the colon, is empty. A name without a namespace is given the 'minecraft' namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the block, with or without the namespace.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use simple_anvil::block::Block;
+    /// assert_eq!(Block::parse("stone").unwrap().name(), "minecraft:stone");
+    /// assert!(Block::parse("").is_none());
+    /// assert!(Block::parse("minecraft:").is_none());
+    /// ```
+    pub fn parse(name: &str) -> Option<Block> {
+        let valid = match name.split_once(':') {
+            Some((namespace, id)) => !namespace.is_empty() && !id.is_empty(),
+            None => !name.is_empty(),
+        };
+        if !valid {
+            return None;
+        }
+        return Some(Block::from_name(name.to_owned(), None, None, None));
+    }
+
+    /// Returns a block from a Chunk palette value. ChunkError::InvalidTag is returned if the value is not a compound
+    /// or has no string Name.
     ///
     /// # Arguments
-    /// * `tag` - The page representing the palette from a Chunk.
-    /// * `coords` - The coordinates of the block, None if not included.
     /// * `tag` - The value for the block from a chunk. This should be a HashMap containing all of the contents of the block.
-    pub fn from_palette(tag: &Value, coords: Option<(i32, i32, i32)>, properties: Option<Vec<(String, String)>>, biome: String) -> Block {
+    /// * `coords` - The coordinates of the block, None if not included.
+    /// * `properties` - The properties of the block, None if it has none.
+    /// * `biome` - The biome the block is in, None if not known.
+    pub fn from_palette(tag: &Value, coords: Option<(i32, i32, i32)>, properties: Option<Vec<(String, String)>>, biome: Option<String>) -> Result<Block, ChunkError> {
         let tag = if let Value::Compound(t) = tag {
             t
         } else {
-            panic!("Tag passed from palette is not compound")
+            return Err(ChunkError::InvalidTag("palette"));
         };
-        let name = if let Value::String(n) = tag.get("Name").unwrap() {
+        let name = if let Some(Value::String(n)) = tag.get("Name") {
             n
         } else {
-            panic!("Palette tag missing name?")
+            return Err(ChunkError::InvalidTag("Name"));
         };
-        return Block::from_name(name.to_string(), coords, properties, biome);
+        return Ok(Block::from_name(name.to_string(), coords, properties, biome));
     }
+
+    /// Returns true if both blocks are the same kind of block in the same state. Unlike ==, the coordinates and
+    /// biome are ignored, and the properties can be in any order.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The block to compare against.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use simple_anvil::block::Block;
+    /// let a = Block::from_name("minecraft:stone".into(), Some((0, 0, 0)), None, None);
+    /// let b = Block::from_name("minecraft:stone".into(), Some((4, 5, 6)), None, None);
+    /// assert!(a != b);
+    /// assert!(a.same_type(&b));
+    /// ```
+    pub fn same_type(&self, other: &Block) -> bool {
+        return state_key(self) == state_key(other);
+    }
+}
+
+/// Assigns a small numeric id to each distinct block state, so that large volumes of blocks can be stored as
+/// integers rather than as strings. Blocks are considered the same state if their namespace, id, and properties
+/// match, the coordinates and biome are ignored. Ids are handed out in the order that states are first seen,
+/// starting at 0.
+#[derive(Clone, Debug, Default)]
+pub struct BlockRegistry {
+    /// The canonical block for each id, stored without coordinates or biome.
+    blocks: Vec<Block>,
+    /// Maps the state key of a block to its id.
+    ids: HashMap<(String, String, Vec<(String, String)>), u32>,
+}
+
+impl BlockRegistry {
+    /// Returns a new empty registry.
+    pub fn new() -> BlockRegistry {
+        return BlockRegistry::default();
+    }
+
+    /// Returns the id for the state of the given block, registering the state if it has not been seen before.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to get the id of.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use simple_anvil::block::{Block, BlockRegistry};
+    /// let mut registry = BlockRegistry::new();
+    /// let stone = registry.intern(&Block::from_name("minecraft:stone".into(), Some((0, 0, 0)), None, None));
+    /// let other = registry.intern(&Block::from_name("minecraft:stone".into(), Some((1, 0, 0)), None, None));
+    /// assert_eq!(stone, other);
+    /// ```
+    pub fn intern(&mut self, block: &Block) -> u32 {
+        let key = state_key(block);
+        if let Some(id) = self.ids.get(&key) {
+            return *id;
+        }
+        let id = self.blocks.len() as u32;
+        self.blocks.push(Block {
+            namespace: block.namespace.clone(),
+            id: block.id.clone(),
+            coords: None,
+            properties: block.properties.clone(),
+            biome: None,
+        });
+        self.ids.insert(key, id);
+        return id;
+    }
+
+    /// Returns the block state for an id previously returned from intern.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id to look up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id was not handed out by this registry.
+    pub fn resolve(&self, id: u32) -> &Block {
+        return &self.blocks[id as usize];
+    }
+
+    /// Returns the id for the state of the given block without registering it, None if it has not been seen.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to look up.
+    pub fn get(&self, block: &Block) -> Option<u32> {
+        return self.ids.get(&state_key(block)).copied();
+    }
+
+    /// Returns the number of distinct block states in the registry.
+    pub fn len(&self) -> usize {
+        return self.blocks.len();
+    }
+
+    /// Returns true if no block states have been registered.
+    pub fn is_empty(&self) -> bool {
+        return self.blocks.is_empty();
+    }
+}
+
+/// Returns a key identifying the state of a block. Properties are sorted since their order in a Chunk is not fixed.
+///
+/// # Arguments
+///
+/// * `block` - The block to build the key for.
+fn state_key(block: &Block) -> (String, String, Vec<(String, String)>) {
+    let mut properties = block.properties.clone().unwrap_or_default();
+    properties.sort();
+    return (block.namespace.clone(), block.id.clone(), properties);
 }
 
 impl fmt::Display for Block {
@@ -116,3 +340,74 @@ impl fmt::Display for Block {
         write!(f, "{}:{}", self.namespace, self.id)
     }
 }
+
+/// Returns the properties of a block from a Chunk palette value, None if the block has no properties.
+///
+/// # Arguments
+///
+/// * `tag` - The palette value for the block.
+pub(crate) fn palette_properties(tag: &Value) -> Option<Vec<(String, String)>> {
+    let tag = if let Value::Compound(t) = tag {
+        t
+    } else {
+        return None;
+    };
+    return match tag.get("Properties") {
+        Some(Value::Compound(p)) => Some(
+            p.iter()
+                .filter_map(|(k, v)| match v {
+                    Value::String(s) => Some((k.to_owned(), s.to_owned())),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        _ => None,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_is_usable_after_name() {
+        let block = Block::from_name("minecraft:stone".into(), Some((1, 2, 3)), None, None);
+        assert_eq!(block.name(), "minecraft:stone");
+        assert_eq!(block.id, "stone");
+        assert_eq!(block.coords, Some((1, 2, 3)));
+        assert_eq!(block.name(), block.to_string());
+    }
+
+    #[test]
+    fn full_name_is_an_alias_of_name() {
+        for name in ["minecraft:stone", "stone", "mod:machine", "minecraft:foo:bar"] {
+            let block = Block::from_name(name.into(), None, None, None);
+            assert_eq!(block.full_name(), block.name());
+        }
+    }
+
+    #[test]
+    fn waterlogged_fence_is_waterlogged_and_stone_is_not() {
+        let properties = vec![("waterlogged".to_string(), "true".to_string()), ("north".to_string(), "true".to_string())];
+        let fence = Block::from_name("minecraft:oak_fence".into(), None, Some(properties), None);
+        let dry = Block::from_name("minecraft:oak_fence".into(), None, Some(vec![("waterlogged".to_string(), "false".to_string())]), None);
+        let stone = Block::from_name("minecraft:stone".into(), None, None, None);
+        assert!(fence.is_waterlogged());
+        assert!(!dry.is_waterlogged());
+        assert!(!stone.is_waterlogged());
+    }
+
+    #[test]
+    fn from_palette_returns_errors_for_bad_entries() {
+        let mut entry = HashMap::new();
+        entry.insert("Name".to_string(), Value::String("minecraft:stone".into()));
+        let block = Block::from_palette(&Value::Compound(entry), Some((1, 2, 3)), None, None).unwrap();
+        assert_eq!(block.name(), "minecraft:stone");
+        assert_eq!(block.coords, Some((1, 2, 3)));
+        assert_eq!(Block::from_palette(&Value::Int(1), None, None, None), Err(ChunkError::InvalidTag("palette")));
+        assert_eq!(Block::from_palette(&Value::Compound(HashMap::new()), None, None, None), Err(ChunkError::InvalidTag("Name")));
+        let mut entry = HashMap::new();
+        entry.insert("Name".to_string(), Value::Int(1));
+        assert_eq!(Block::from_palette(&Value::Compound(entry), None, None, None), Err(ChunkError::InvalidTag("Name")));
+    }
+}
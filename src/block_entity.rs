@@ -0,0 +1,43 @@
+use nbt::Value;
+
+/// The extra data stored for a block that needs more than its block state, such as a chest, sign, or spawner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockEntity {
+    /// The full name of the block entity, ie. 'minecraft:chest'
+    pub id: String,
+    /// The x coordinate of the block in world coordinates.
+    pub x: i32,
+    /// The y coordinate of the block in world coordinates.
+    pub y: i32,
+    /// The z coordinate of the block in world coordinates.
+    pub z: i32,
+    /// The complete compound for the block entity, for reading things like sign text or chest contents.
+    pub data: Value,
+}
+
+impl BlockEntity {
+    /// Returns a BlockEntity from a compound found in a chunk's block entity list. None is returned if the compound
+    /// is missing an id or any of its coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The compound for a single block entity.
+    pub fn from_value(tag: &Value) -> Option<BlockEntity> {
+        let compound = if let Value::Compound(c) = tag {
+            c
+        } else {
+            return None;
+        };
+        let id = if let Some(Value::String(id)) = compound.get("id") {
+            id.to_owned()
+        } else {
+            return None;
+        };
+        return match (compound.get("x"), compound.get("y"), compound.get("z")) {
+            (Some(Value::Int(x)), Some(Value::Int(y)), Some(Value::Int(z))) => {
+                Some(BlockEntity { id, x: *x, y: *y, z: *z, data: tag.clone() })
+            }
+            _ => None,
+        };
+    }
+}
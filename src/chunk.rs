@@ -1,8 +1,138 @@
 use nbt::{Blob, Value};
 
-use crate::{block::Block, region::Region};
+use crate::{block::{self, Block}, block_entity::BlockEntity, entity::Entity, region::Region, section::{self, Section}};
 
-use std::{cmp, collections::HashMap};
+use std::{cmp, collections::HashMap, error, fmt, ops::Range};
+
+/// The different heightmaps that a Chunk stores.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum HeightmapKind {
+    /// The highest block that is not air.
+    WorldSurface,
+    /// The highest block that is solid, water and other fluids are ignored.
+    OceanFloor,
+    /// The highest block that blocks motion or contains a fluid.
+    MotionBlocking,
+    /// The same as MotionBlocking except leaves are ignored.
+    MotionBlockingNoLeaves,
+}
+
+impl HeightmapKind {
+    /// Returns the name of the tag that the heightmap is stored under, ie. 'WORLD_SURFACE'
+    pub fn tag_name(&self) -> &'static str {
+        return match self {
+            HeightmapKind::WorldSurface => "WORLD_SURFACE",
+            HeightmapKind::OceanFloor => "OCEAN_FLOOR",
+            HeightmapKind::MotionBlocking => "MOTION_BLOCKING",
+            HeightmapKind::MotionBlockingNoLeaves => "MOTION_BLOCKING_NO_LEAVES",
+        };
+    }
+}
+
+/// The generation stages that a Chunk goes through, in order. Since 1.20 the status is stored with a namespace
+/// ('minecraft:full') while older versions store the bare name ('full'), both forms are accepted.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ChunkStatus {
+    Empty,
+    StructureStarts,
+    StructureReferences,
+    Biomes,
+    Noise,
+    Surface,
+    Carvers,
+    LiquidCarvers,
+    Features,
+    InitializeLight,
+    Light,
+    Spawn,
+    Heightmaps,
+    /// The Chunk is completely generated.
+    Full,
+    /// A status that this crate does not know about, with the namespace removed.
+    Other(String),
+}
+
+impl ChunkStatus {
+    /// Returns the status matching a Status tag, with or without the 'minecraft:' namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The value of the Status tag.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use simple_anvil::chunk::ChunkStatus;
+    /// assert_eq!(ChunkStatus::from_name("minecraft:full"), ChunkStatus::Full);
+    /// assert_eq!(ChunkStatus::from_name("full"), ChunkStatus::Full);
+    /// ```
+    pub fn from_name(name: &str) -> ChunkStatus {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        return match name {
+            "empty" => ChunkStatus::Empty,
+            "structure_starts" => ChunkStatus::StructureStarts,
+            "structure_references" => ChunkStatus::StructureReferences,
+            "biomes" => ChunkStatus::Biomes,
+            "noise" => ChunkStatus::Noise,
+            "surface" => ChunkStatus::Surface,
+            "carvers" => ChunkStatus::Carvers,
+            "liquid_carvers" => ChunkStatus::LiquidCarvers,
+            "features" => ChunkStatus::Features,
+            "initialize_light" => ChunkStatus::InitializeLight,
+            "light" => ChunkStatus::Light,
+            "spawn" => ChunkStatus::Spawn,
+            "heightmaps" => ChunkStatus::Heightmaps,
+            "full" => ChunkStatus::Full,
+            other => ChunkStatus::Other(other.to_string()),
+        };
+    }
+}
+
+/// The carving masks that a Chunk stores while it is being generated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum CarveKind {
+    /// Blocks removed by cave carvers.
+    Air,
+    /// Blocks removed by underwater carvers.
+    Liquid,
+}
+
+impl CarveKind {
+    /// Returns the name of the tag that the mask is stored under, ie. 'AIR'
+    pub fn tag_name(&self) -> &'static str {
+        return match self {
+            CarveKind::Air => "AIR",
+            CarveKind::Liquid => "LIQUID",
+        };
+    }
+}
+
+/// The errors that can occur while decoding the contents of a Chunk. Methods that return an Option give None for
+/// these, with the 'no-panic' feature enabled the same checks are available through methods that return them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ChunkError {
+    /// A tag that the Chunk needs is missing.
+    MissingTag(&'static str),
+    /// A tag is present but holds the wrong type of value, or a value that is out of range.
+    InvalidTag(&'static str),
+    /// The section y index is outside of the range of sections a Chunk can have.
+    SectionOutOfRange(i8),
+    /// The x, y, z coordinate is outside of the Chunk or outside of the height of the world.
+    CoordinateOutOfRange(i32, i32, i32),
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChunkError::MissingTag(name) => write!(f, "chunk is missing the {} tag", name),
+            ChunkError::InvalidTag(name) => write!(f, "chunk has an invalid {} tag", name),
+            ChunkError::SectionOutOfRange(y) => write!(f, "section y index {} is out of range", y),
+            ChunkError::CoordinateOutOfRange(x, y, z) => write!(f, "coordinate {}, {}, {} is outside of the chunk", x, y, z),
+        }
+    }
+}
+
+impl error::Error for ChunkError {}
 
 /// A simple representation of a Minecraft Chunk
 #[derive(Clone)]
@@ -15,6 +145,8 @@ pub struct Chunk {
     pub z: u32,
     /// Contains the biome information for each section
     biome_data: Option<[[String; 64]; 24]>,
+    /// Whether the chunk was stored as little-endian NBT rather than the standard big-endian.
+    little_endian: bool,
 }
 
 impl Chunk {
@@ -27,275 +159,1671 @@ impl Chunk {
     /// * `chunk_x` - The x coordinate within the Region of the Chunk
     /// * `chunk_z` - The z coordinate within the Region of the Chunk
     pub fn from_region(region: & Region, chunk_x: u32, chunk_z: u32) -> Option<Chunk> {
-        match region.chunk_data(chunk_x, chunk_z) {
-            Some(data) => {
-                return Some(Chunk{ data, x: chunk_x, z: chunk_z, biome_data: None });
+        match region.read_chunk(chunk_x, chunk_z).ok().flatten() {
+            Some((data, little_endian)) => {
+                return Some(Chunk::from_data(data, chunk_x, chunk_z, little_endian));
             }
             None => None,
         }
     }
 
-    /// Returns a string representing the current generation state of the Chunk. 'full' is completely generated.
-    /// 
+    /// Returns a chunk from data that has already been read out of a region.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The chunk data
+    /// * `chunk_x` - The x coordinate within the Region of the Chunk
+    /// * `chunk_z` - The z coordinate within the Region of the Chunk
+    /// * `little_endian` - Whether the data was stored as little-endian NBT
+    pub(crate) fn from_data(data: Box<Blob>, chunk_x: u32, chunk_z: u32, little_endian: bool) -> Chunk {
+        return Chunk{ data, x: chunk_x, z: chunk_z, biome_data: None, little_endian };
+    }
+
+    /// Returns true if the Chunk was stored as little-endian NBT. Minecraft always writes big-endian NBT, so this
+    /// means the region file was written by a non-standard tool and the rest of the file may deserve suspicion.
+    pub fn is_little_endian(&self) -> bool {
+        return self.little_endian;
+    }
+
+    /// Returns the NBT of the Chunk exactly as it was read, for reading tags that this crate does not have a method
+    /// for. The layout of the NBT depends on the version of Minecraft that saved the Chunk, before 1.18 most tags
+    /// are inside of a 'Level' compound and many tags have been renamed over time, so check get_data_version
+    /// before relying on a particular layout.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(structures) = chunk.nbt().get("structures") {
+    ///     println!("{:?}", structures);
+    /// }
+    /// ```
+    pub fn nbt(&self) -> &Blob {
+        return &self.data;
+    }
+
+    /// Returns the DataVersion of the Chunk, which identifies the version of Minecraft that last saved it. Chunks
+    /// use the 1.18 layout from DataVersion 2860 onwards. Chunks saved before DataVersion existed (1.9) return 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if chunk.get_data_version() >= 2860 {
+    ///     println!("1.18 or newer");
+    /// }
+    /// ```
+    pub fn get_data_version(&self) -> i32 {
+        return match self.data.get("DataVersion") {
+            Some(Value::Int(v)) => *v,
+            _ => 0,
+        };
+    }
+
+    /// Returns the absolute x coordinate of the Chunk stored in its xPos tag, None if the tag is missing. Unlike the
+    /// x field, which is the position within the Region that the Chunk was read from, this is the position in the
+    /// world that the Chunk was saved with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.-1.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// assert_eq!(chunk.get_x_pos(), Some(-32));
+    /// ```
+    pub fn get_x_pos(&self) -> Option<i32> {
+        return match self.level_tag("xPos", "xPos") {
+            Some(Value::Int(x)) => Some(*x),
+            _ => None,
+        };
+    }
+
+    /// Returns the index of the lowest section of the Chunk stored in its yPos tag, ie. -4 for the default world
+    /// height where blocks start at y=-64. Worlds with a custom height can start lower or higher. None is returned
+    /// if the tag is missing, which is always the case for chunks saved before 1.18.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(y) = chunk.get_y_pos() {
+    ///     println!("Blocks start at y={}", y * 16);
+    /// }
+    /// ```
+    pub fn get_y_pos(&self) -> Option<i32> {
+        return match self.data.get("yPos") {
+            Some(Value::Int(y)) => Some(*y),
+            _ => None,
+        };
+    }
+
+    /// Returns the absolute z coordinate of the Chunk stored in its zPos tag, None if the tag is missing. Unlike the
+    /// z field, which is the position within the Region that the Chunk was read from, this is the position in the
+    /// world that the Chunk was saved with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.-1.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 31).unwrap();
+    /// assert_eq!(chunk.get_z_pos(), Some(-1));
+    /// ```
+    pub fn get_z_pos(&self) -> Option<i32> {
+        return match self.level_tag("zPos", "zPos") {
+            Some(Value::Int(z)) => Some(*z),
+            _ => None,
+        };
+    }
+
+    /// Returns a string representing the current generation state of the Chunk. 'full' is completely generated, since
+    /// 1.20 this includes the namespace ('minecraft:full'), use status to compare against either form. None is
+    /// returned if the Chunk has no Status tag, as is the case for some proto-chunks.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if chunk.get_status() == Some("full") {
+    ///     println!("Fully Generated!");
+    /// }
+    /// ```
+    pub fn get_status(&self) -> Option<&str> {
+        return self.status_result().ok().map(|s| s.as_str());
+    }
+
+    /// Returns the Status tag of the Chunk, or the reason it could not be read.
+    fn status_result(&self) -> Result<&String, ChunkError> {
+        return match self.level_tag("Status", "Status") {
+            Some(Value::String(s)) => Ok(s),
+            Some(_) => Err(ChunkError::InvalidTag("Status")),
+            None => Err(ChunkError::MissingTag("Status")),
+        };
+    }
+
+    /// Returns the current generation state of the Chunk, None if the Chunk has no Status tag. Unlike get_status
+    /// this handles both the namespaced and bare forms of the status.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::{chunk::ChunkStatus, region::Region};
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if chunk.status() == Some(ChunkStatus::Full) {
+    ///     println!("Fully Generated!");
+    /// }
+    /// ```
+    pub fn status(&self) -> Option<ChunkStatus> {
+        return match self.level_tag("Status", "Status") {
+            Some(Value::String(s)) => Some(ChunkStatus::from_name(s)),
+            _ => None,
+        };
+    }
+
+    /// Returns true if the Chunk has finished generating.
+    pub fn is_fully_generated(&self) -> bool {
+        return self.status() == Some(ChunkStatus::Full);
+    }
+
+    /// Returns an i64 (equivalent of Java long) of the last tick at which the chunk updated. None is returned if the
+    /// Chunk is missing the LastUpdate tag or it is not a long.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(tick) = chunk.get_last_update() {
+    ///     println!("{}", tick);
+    /// }
+    /// ```
+    pub fn get_last_update(&self) -> Option<i64> {
+        return match self.level_tag("LastUpdate", "LastUpdate") {
+            Some(Value::Long(l)) => Some(*l),
+            _ => None,
+        };
+    }
+
+    /// Returns the number of ticks that players have spent in the Chunk, added up over all players, or None if the
+    /// Chunk is missing the InhabitedTime tag. Chunks with a large inhabited time are usually near a base.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if chunk.get_inhabited_time().unwrap_or(0) > 72000 {
+    ///     println!("Players have spent over an hour here");
+    /// }
+    /// ```
+    pub fn get_inhabited_time(&self) -> Option<i64> {
+        return match self.level_tag("InhabitedTime", "InhabitedTime") {
+            Some(Value::Long(l)) => Some(*l),
+            _ => None,
+        };
+    }
+
+    /// Returns the last time at which the chunk updated as a number of in-game days, where a day is 24000 ticks.
+    /// None is returned if the Chunk is missing the LastUpdate tag.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(days) = chunk.last_update_game_days() {
+    ///     println!("Last updated on day {:.1}", days);
+    /// }
+    /// ```
+    pub fn last_update_game_days(&self) -> Option<f64> {
+        return Some(self.get_last_update()? as f64 / 24000.0);
+    }
+
+    /// Returns the status that the Chunk still needs to be generated up to below y=0. This is only present on
+    /// chunks from worlds upgraded from before 1.18 where the deepslate layers have not been generated yet, None
+    /// is returned for every other Chunk.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(target) = chunk.retrogen_target() {
+    ///     println!("Still needs to be generated up to {}", target);
+    /// }
+    /// ```
+    pub fn retrogen_target(&self) -> Option<String> {
+        if let Some(Value::Compound(retrogen)) = self.data.get("below_zero_retrogen") {
+            if let Some(Value::String(s)) = retrogen.get("target_status") {
+                return Some(s.to_owned());
+            }
+        }
+        return None;
+    }
+
+    /// Returns all of the entities stored in the Chunk. Since 1.17 entities are stored in the separate entities
+    /// region files rather than with the blocks, so a Chunk from a normal region file will usually have none.
+    /// Entities that are missing an id or position are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("entities/r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// for entity in chunk.get_entities() {
+    ///     println!("{} at {:?}", entity.id, entity.pos);
+    /// }
+    /// ```
+    pub fn get_entities(&self) -> Vec<Entity> {
+        return self.raw_entities().into_iter().filter_map(Entity::from_value).collect();
+    }
+
+    /// Returns all of the block entities stored in the Chunk, such as chests, signs, and spawners. Block entities
+    /// that are missing an id or coordinates are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// for block_entity in chunk.get_block_entities() {
+    ///     println!("{} at {}, {}, {}", block_entity.id, block_entity.x, block_entity.y, block_entity.z);
+    /// }
+    /// ```
+    pub fn get_block_entities(&self) -> Vec<BlockEntity> {
+        return self.raw_block_entities().into_iter().filter_map(BlockEntity::from_value).collect();
+    }
+
+    /// Returns the block entity at a particular position, None if there is no block entity there. Block entities
+    /// store their position in world coordinates, so x, y, and z are world coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate of the block.
+    /// * `y` - The y coordinate of the block.
+    /// * `z` - The z coordinate of the block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(chest) = chunk.get_block_entity(5, 64, 11) {
+    ///     println!("{:?}", chest.data);
+    /// }
+    /// ```
+    pub fn get_block_entity(&self, x: i32, y: i32, z: i32) -> Option<BlockEntity> {
+        return self
+            .raw_block_entities()
+            .into_iter()
+            .filter_map(BlockEntity::from_value)
+            .find(|b| b.x == x && b.y == y && b.z == z);
+    }
+
+    /// Returns the compound of every entity stored in the Chunk exactly as it is stored. This handles both the
+    /// current layout and the one used before 1.18 where entities are inside the Level compound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("entities/r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// println!("{} entities", chunk.raw_entities().len());
+    /// ```
+    pub fn raw_entities(&self) -> Vec<&Value> {
+        return match self.level_tag("Entities", "Entities") {
+            Some(Value::List(l)) => l.iter().collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    /// Returns the compound of every block entity (chests, signs, spawners, etc.) stored in the Chunk exactly as
+    /// it is stored. This handles both the current layout and the one used before 1.18 where block entities are
+    /// kept in the TileEntities list inside the Level compound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// for block_entity in chunk.raw_block_entities() {
+    ///     println!("{:?}", block_entity);
+    /// }
+    /// ```
+    pub fn raw_block_entities(&self) -> Vec<&Value> {
+        return match self.level_tag("block_entities", "TileEntities") {
+            Some(Value::List(l)) => l.iter().collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    /// Returns the world coordinates of every block that is waiting for a post-processing update, such as blocks
+    /// placed by world generation whose shape still needs to be updated from its neighbours. PostProcessing holds a
+    /// list of packed positions for each section starting from the lowest, these are decoded into absolute block
+    /// coordinates using the xPos and zPos of the Chunk. An empty vector is returned if the Chunk has nothing to
+    /// post-process or is missing its position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// for (x, y, z) in chunk.get_post_processing() {
+    ///     println!("{}, {}, {}", x, y, z);
+    /// }
+    /// ```
+    pub fn get_post_processing(&self) -> Vec<(i32, i32, i32)> {
+        let sections = match self.level_tag("PostProcessing", "PostProcessing") {
+            Some(Value::List(l)) => l,
+            _ => return Vec::new(),
+        };
+        let (chunk_x, chunk_z) = match self.world_coords() {
+            Some(c) => c,
+            None => return Vec::new(),
+        };
+        let min_section = self.min_y().div_euclid(16);
+        let mut positions = Vec::new();
+        for (index, section) in sections.iter().enumerate() {
+            let packed = if let Value::List(p) = section {
+                p
+            } else {
+                continue;
+            };
+            for position in packed {
+                if let Value::Short(s) = position {
+                    let (x, y, z) = (*s as i32 & 15, (*s as i32 >> 4) & 15, (*s as i32 >> 8) & 15);
+                    positions.push((chunk_x * 16 + x, (min_section + index as i32) * 16 + y, chunk_z * 16 + z));
+                }
+            }
+        }
+        return positions;
+    }
+
+    /// Returns a tag from the root of the Chunk, or from inside the Level compound for chunks from before 1.18.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tag in the current layout.
+    /// * `old_name` - The name of the tag inside the Level compound.
+    fn level_tag(&self, name: &str, old_name: &str) -> Option<&Value> {
+        return match self.data.get(name) {
+            Some(v) => Some(v),
+            None => match self.data.get("Level") {
+                Some(Value::Compound(level)) => level.get(old_name),
+                _ => None,
+            },
+        };
+    }
+
+    /// Returns a heightmap of the Chunk, giving the y of the highest block in each of the 256 columns ordered by z
+    /// and then x. Columns at the bottom of the world are included. If the Chunk is not fully generated or is missing
+    /// the heightmap then a None is returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `ignore_water` - Determines which heightmap to return, if true then a heightmap that does not take into account the water is returned (OCEAN_FLOOR), if false then the water is accounted for (WORLD_SURFACE).
+    /// 
+    ///  # Examples
+    /// 
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let heightmap = chunk.get_heightmap(false);
+    /// ```
+    pub fn get_heightmap(&self, ignore_water: bool) -> Option<Vec<i32>> {
+        let kind = if ignore_water {
+            HeightmapKind::OceanFloor
+        } else {
+            HeightmapKind::WorldSurface
+        };
+        return self.get_heightmap_kind(kind);
+    }
+
+    /// Returns one of the heightmaps of the Chunk. If the Chunk is not fully generated, or the heightmap is missing or
+    /// not stored as a long array, then a None is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The heightmap to return.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::{chunk::HeightmapKind, region::Region};
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let heightmap = chunk.get_heightmap_kind(HeightmapKind::MotionBlocking);
+    /// ```
+    pub fn get_heightmap_kind(&self, kind: HeightmapKind) -> Option<Vec<i32>> {
+        return self.heightmap_result(kind).ok().flatten();
+    }
+
+    /// Returns a heightmap of the Chunk as a grid indexed by the x and then z coordinate of each column within the
+    /// Chunk, so the height at x, z is at [x][z]. Every column is included, even those at the bottom of the world.
+    /// Each height is the y of the highest block in the column. If the Chunk is not fully generated or is missing
+    /// the heightmap then None is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `ignore_water` - If true the OCEAN_FLOOR heightmap is used, otherwise the WORLD_SURFACE heightmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(grid) = chunk.get_heightmap_grid(false) {
+    ///     println!("Height at 5, 11: {}", grid[5][11]);
+    /// }
+    /// ```
+    pub fn get_heightmap_grid(&self, ignore_water: bool) -> Option<[[i32; 16]; 16]> {
+        if !self.is_fully_generated() {
+            return None;
+        }
+        let kind = if ignore_water {
+            HeightmapKind::OceanFloor
+        } else {
+            HeightmapKind::WorldSurface
+        };
+        let heights = self.heightmap(kind)?;
+        let mut grid = [[0; 16]; 16];
+        for (index, height) in heights.into_iter().take(256).enumerate() {
+            grid[index % 16][index / 16] = height;
+        }
+        return Some(grid);
+    }
+
+    /// Returns the heightmap that get_heightmap_kind returns, or the reason it could not be read.
+    fn heightmap_result(&self, kind: HeightmapKind) -> Result<Option<Vec<i32>>, ChunkError> {
+        if self.is_fully_generated() {
+            let height_maps = match self.level_tag("Heightmaps", "Heightmaps") {
+                Some(Value::Compound(hm)) => hm,
+                Some(_) => return Err(ChunkError::InvalidTag("Heightmaps")),
+                None => return Err(ChunkError::MissingTag("Heightmaps")),
+            };
+
+            let map = kind.tag_name();
+
+            let surface = match height_maps.get(map) {
+                Some(Value::LongArray(la)) => la,
+                Some(_) => return Err(ChunkError::InvalidTag(map)),
+                None => return Err(ChunkError::MissingTag(map)),
+            };
+
+            return Ok(Some(decode_heightmap(surface, self.min_y())));
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the packed long array of a heightmap exactly as it is stored, without decoding it. None is returned
+    /// if the Chunk does not have the heightmap, which is usually the case for chunks that are not fully generated.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The heightmap to return.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::{chunk::HeightmapKind, region::Region};
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let packed = chunk.heightmap_raw(HeightmapKind::MotionBlocking);
+    /// ```
+    pub fn heightmap_raw(&self, kind: HeightmapKind) -> Option<Vec<i64>> {
+        return self.heightmap_longs(kind).map(|la| la.to_vec());
+    }
+
+    /// Returns several decoded heightmaps at once, giving the y of the highest block in each column. The columns
+    /// are ordered by z and then x, so the column at x, z is at index z * 16 + x. The heightmaps are all read from
+    /// a single lookup of the Heightmaps tag, kinds that the Chunk does not have are left out of the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `kinds` - The heightmaps to decode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::{chunk::HeightmapKind, region::Region};
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let maps = chunk.heightmaps_multi(&[HeightmapKind::WorldSurface, HeightmapKind::OceanFloor]);
+    /// if let (Some(surface), Some(floor)) = (maps.get(&HeightmapKind::WorldSurface), maps.get(&HeightmapKind::OceanFloor)) {
+    ///     println!("Water depth at 0, 0: {}", surface[0] - floor[0]);
+    /// }
+    /// ```
+    pub fn heightmaps_multi(&self, kinds: &[HeightmapKind]) -> HashMap<HeightmapKind, Vec<i32>> {
+        let mut maps = HashMap::new();
+        let height_maps = match self.heightmaps_tag() {
+            Some(hm) => hm,
+            None => return maps,
+        };
+        let min_y = self.min_y();
+        for kind in kinds {
+            if let Some(Value::LongArray(la)) = height_maps.get(kind.tag_name()) {
+                maps.insert(*kind, decode_heightmap(la, min_y));
+            }
+        }
+        return maps;
+    }
+
+    /// Returns how deep the water is in a column, the difference between the WORLD_SURFACE and OCEAN_FLOOR
+    /// heightmaps. 0 is returned for columns without water, for coordinates outside of the Chunk, and if the Chunk
+    /// is missing either heightmap.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate of the column within the Chunk (0-15)
+    /// * `z` - The z coordinate of the column within the Chunk (0-15)
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// println!("{} blocks deep", chunk.water_depth(3, 9));
+    /// ```
+    pub fn water_depth(&self, x: i32, z: i32) -> i32 {
+        if !(0..16).contains(&x) || !(0..16).contains(&z) {
+            return 0;
+        }
+        let maps = self.heightmaps_multi(&[HeightmapKind::WorldSurface, HeightmapKind::OceanFloor]);
+        return match (maps.get(&HeightmapKind::WorldSurface), maps.get(&HeightmapKind::OceanFloor)) {
+            (Some(surface), Some(floor)) => {
+                let column = (z * 16 + x) as usize;
+                cmp::max(surface[column] - floor[column], 0)
+            }
+            _ => 0,
+        };
+    }
+
+    /// Returns the compound containing all of the Chunk's heightmaps, this is inside the Level compound before 1.18.
+    fn heightmaps_tag(&self) -> Option<&HashMap<String, Value>> {
+        return if let Some(Value::Compound(hm)) = self.level_tag("Heightmaps", "Heightmaps") {
+            Some(hm)
+        } else {
+            None
+        };
+    }
+
+    /// Returns the packed long array of a heightmap, None if the Chunk does not have the heightmap.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The heightmap to return.
+    fn heightmap_longs(&self, kind: HeightmapKind) -> Option<&[i64]> {
+        return match self.heightmaps_tag()?.get(kind.tag_name()) {
+            Some(Value::LongArray(la)) => Some(la),
+            _ => None,
+        };
+    }
+
+    /// Returns an approximate normal of the terrain surface at a column, for shading rendered maps. The normal is
+    /// found from the difference in height between the neighbouring columns of the OCEAN_FLOOR heightmap, so only
+    /// solid blocks are considered. Columns on the edge of the Chunk only use the neighbours inside the Chunk. The
+    /// returned vector has a length of 1 with y pointing up, if the Chunk has no heightmap then (0, 1, 0) is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate of the column within the Chunk (0-15)
+    /// * `z` - The z coordinate of the column within the Chunk (0-15)
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let (nx, ny, nz) = chunk.surface_normal(7, 7);
+    /// // Light coming from the north west.
+    /// let shade = (-nx - nz + ny).max(0.0);
+    /// ```
+    pub fn surface_normal(&self, x: i32, z: i32) -> (f32, f32, f32) {
+        let heights = match self.heightmap(HeightmapKind::OceanFloor) {
+            Some(h) => h,
+            None => return (0.0, 1.0, 0.0),
+        };
+        let height = |x: i32, z: i32| heights[(z.clamp(0, 15) * 16 + x.clamp(0, 15)) as usize] as f32;
+        let (x, z) = (x.clamp(0, 15), z.clamp(0, 15));
+        let (left, right) = (cmp::max(x - 1, 0), cmp::min(x + 1, 15));
+        let (back, front) = (cmp::max(z - 1, 0), cmp::min(z + 1, 15));
+        let dx = (height(right, z) - height(left, z)) / (right - left) as f32;
+        let dz = (height(x, front) - height(x, back)) / (front - back) as f32;
+        let length = (dx * dx + 1.0 + dz * dz).sqrt();
+        return (-dx / length, 1.0 / length, -dz / length);
+    }
+
+    /// Returns the y of the highest block in a column that does not have full sky light, which is the true surface
+    /// of the column. Unlike the heightmaps this accounts for overhangs, and blocks that filter light such as water
+    /// and leaves count as the surface. Sections missing their sky light are treated as open sky. None is returned
+    /// if the Chunk has no sky light stored or if the whole column has full sky light.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate of the column within the Chunk (0-15)
+    /// * `z` - The z coordinate of the column within the Chunk (0-15)
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(y) = chunk.sky_exposed_height(5, 11) {
+    ///     println!("The surface is at {}", y);
+    /// }
+    /// ```
+    pub fn sky_exposed_height(&self, x: i32, z: i32) -> Option<i32> {
+        if !(0..16).contains(&x) || !(0..16).contains(&z) {
+            return None;
+        }
+        let mut sections = self.section_list();
+        sections.sort_by(|a, b| b.y.cmp(&a.y));
+        for section in sections {
+            for y in (0..16).rev() {
+                match section.light("SkyLight", x, y, z) {
+                    Some(15) => {}
+                    Some(_) => return Some(section.y as i32 * 16 + y),
+                    None => break,
+                }
+            }
+        }
+        return None;
+    }
+
+    /// Returns true if slimes can spawn in the Chunk below y = 40 in a world with the given seed, using the same
+    /// calculation as Minecraft. The absolute position of the Chunk is read from its xPos and zPos tags, false is
+    /// returned if they are missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `world_seed` - The seed of the world the Chunk is from.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if chunk.is_slime_chunk(12345) {
+    ///     println!("Slime chunk!");
+    /// }
+    /// ```
+    pub fn is_slime_chunk(&self, world_seed: i64) -> bool {
+        let (x, z) = match self.world_coords() {
+            Some(c) => c,
+            None => return false,
+        };
+        // The int multiplications intentionally overflow, as they do in Java.
+        let seed = world_seed
+            .wrapping_add(x.wrapping_mul(x).wrapping_mul(0x4c1906) as i64)
+            .wrapping_add(x.wrapping_mul(0x5ac0db) as i64)
+            .wrapping_add((z.wrapping_mul(z) as i64).wrapping_mul(0x4307a7))
+            .wrapping_add(z.wrapping_mul(0x5f24f) as i64)
+            ^ 0x3ad8025f;
+        return java_random_next_int(seed, 10) == 0;
+    }
+
+    /// Returns true if the xPos and zPos tags of the Chunk match where it was found in the region. A mismatch
+    /// means the chunk was written to the wrong slot, which only happens through corruption or a bad edit. If the
+    /// region filename does not give the region coordinates then only the position within the region is checked.
+    /// Chunks without position tags are reported as not matching.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The region the Chunk was read from.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// for chunk in region.chunks_excluding(&Default::default()) {
+    ///     if !chunk.position_matches_region(&region) {
+    ///         println!("Chunk {}, {} is misplaced", chunk.x, chunk.z);
+    ///     }
+    /// }
+    /// ```
+    pub fn position_matches_region(&self, region: &Region) -> bool {
+        let (x, z) = match self.world_coords() {
+            Some(coords) => coords,
+            None => return false,
+        };
+        return match region.coords() {
+            Some((region_x, region_z)) => x == region_x * 32 + self.x as i32 && z == region_z * 32 + self.z as i32,
+            None => x.rem_euclid(32) == self.x as i32 && z.rem_euclid(32) == self.z as i32,
+        };
+    }
+
+    /// Returns the world coordinates of a block in the Chunk, used so that every way of reading blocks gives the
+    /// same coordinates. Chunks are 16 blocks wide, and the position of the Chunk comes from its xPos and zPos tags,
+    /// falling back to its position within the region if they are missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate within the Chunk (0-15).
+    /// * `y` - The y coordinate of the block.
+    /// * `z` - The z coordinate within the Chunk (0-15).
+    fn block_coords(&self, x: i32, y: i32, z: i32) -> (i32, i32, i32) {
+        let (chunk_x, chunk_z) = self.world_coords().unwrap_or((self.x as i32, self.z as i32));
+        return (chunk_x * 16 + x, y, chunk_z * 16 + z);
+    }
+
+    /// Returns the absolute chunk coordinates of the Chunk from its xPos and zPos tags, None if they are missing.
+    fn world_coords(&self) -> Option<(i32, i32)> {
+        return Some((self.get_x_pos()?, self.get_z_pos()?));
+    }
+
+    /// Returns the decoded heightmap of the given kind, giving the y of the highest block in each column. The
+    /// columns are ordered by z and then x, so the column at x, z is at index z * 16 + x. None is returned if the
+    /// Chunk does not have the heightmap.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The heightmap to decode.
+    fn heightmap(&self, kind: HeightmapKind) -> Option<Vec<i32>> {
+        return Some(decode_heightmap(self.heightmap_longs(kind)?, self.min_y()));
+    }
+
+    /// Returns true if the Chunk was saved before 1.18, when everything in the Chunk was kept inside a Level
+    /// compound.
+    fn is_pre_1_18(&self) -> bool {
+        return matches!(self.data.get("Level"), Some(Value::Compound(_)));
+    }
+
+    /// Returns the lowest y coordinate that blocks can be placed at in the Chunk. This comes from the yPos tag,
+    /// chunks that are missing it are assumed to use the default world height starting at -64. Chunks saved before
+    /// 1.18 always start at 0.
+    fn min_y(&self) -> i32 {
+        if self.is_pre_1_18() {
+            return 0;
+        }
+        return self.get_y_pos().map(|y| y * 16).unwrap_or(-64);
+    }
+
+    /// Returns the y indices of the sections that can hold blocks in the Chunk. The range starts at the section
+    /// containing min_y and covers at least the default height, 24 sections or 16 for chunks saved before 1.18.
+    /// Sections stored above the default height extend the range, so worlds whose height has been changed by a
+    /// datapack or mod are handled.
+    fn section_range(&self) -> Range<i32> {
+        return self.section_range_of(&self.section_list());
+    }
+
+    /// Returns the same range as section_range, using sections that have already been listed.
+    ///
+    /// # Arguments
+    ///
+    /// * `sections` - Every section in the Chunk, as given by section_list.
+    fn section_range_of(&self, sections: &[Section]) -> Range<i32> {
+        let lowest = self.min_y().div_euclid(16);
+        let default = if self.is_pre_1_18() { 16 } else { 24 };
+        let highest = match sections.iter().map(|s| s.y as i32 + 1).max() {
+            Some(top) => cmp::max(top, lowest + default),
+            None => lowest + default,
+        };
+        return lowest..highest;
+    }
+
+    /// Returns whether the block at a particular x, y, z coordinate within the Chunk was removed by a carver while
+    /// the Chunk was generated. Carving masks are only kept until a Chunk finishes generating, so None is returned
+    /// for fully generated chunks, and for coordinates outside of the Chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate within the Chunk (0-15)
+    /// * `y` - The y coordinate of the block
+    /// * `z` - The z coordinate within the Chunk (0-15)
+    /// * `kind` - The carving mask to check.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::{chunk::CarveKind, region::Region};
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if chunk.is_carved(5, -12, 11, CarveKind::Air) == Some(true) {
+    ///     println!("Part of a cave");
+    /// }
+    /// ```
+    pub fn is_carved(&self, x: i32, y: i32, z: i32, kind: CarveKind) -> Option<bool> {
+        if !(0..16).contains(&x) || !(0..16).contains(&z) {
+            return None;
+        }
+        // Before 1.18 the masks were kept in the Level compound and the world always started at y = 0.
+        let (masks, min_y) = match self.data.get("CarvingMasks") {
+            Some(m) => (m, self.min_y()),
+            None => match self.data.get("Level") {
+                Some(Value::Compound(level)) => (level.get("CarvingMasks")?, 0),
+                _ => return None,
+            },
+        };
+        let mask = if let Value::Compound(m) = masks {
+            m.get(kind.tag_name())?
+        } else {
+            return None;
+        };
+        if y < min_y {
+            return None;
+        }
+        let bit = (x | z << 4 | (y - min_y) << 8) as usize;
+        return match mask {
+            Value::LongArray(longs) => Some(longs.get(bit / 64).map_or(false, |l| (*l as u64 >> (bit % 64)) & 1 == 1)),
+            Value::ByteArray(bytes) => Some(bytes.get(bit / 8).map_or(false, |b| (*b as u8 >> (bit % 8)) & 1 == 1)),
+            _ => None,
+        };
+    }
+
+    /// Returns a vertical section of a Chunk, borrowed from the Chunk's data rather than copied. The caller is
+    /// expected to have checked y against section_range.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `y` - The y index of the section.
+    fn get_section(&self, y: i8) -> Result<Option<&HashMap<String, Value>>, ChunkError> {
+        let sections = match self.data.get("sections") {
+            Some(Value::List(s)) => s,
+            Some(_) => return Err(ChunkError::InvalidTag("sections")),
+            None => return Err(ChunkError::MissingTag("sections")),
+        };
+
+        for section in sections {
+            let section = if let Value::Compound(s) = section {
+                s
+            } else {
+                return Err(ChunkError::InvalidTag("sections"));
+            };
+            let section_y = match section.get("Y") {
+                Some(Value::Byte(sec_y)) => sec_y,
+                Some(_) => return Err(ChunkError::InvalidTag("Y")),
+                None => return Err(ChunkError::MissingTag("Y")),
+            };
+            if *section_y == y {
+                return Ok(Some(section));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the block at a particular x, y, z coordinate within a chunk. x and z should be the coordinates within the Chunk (0-15).
+    /// y must be within the height of the world, from -64 up to 319 for chunks saved since 1.18, and from 0 up to 255
+    /// for older chunks. Worlds with a custom height start at the chunk's yPos and reach at least as high as the
+    /// default height, or above the highest section stored in the chunk if that is higher. Coordinates outside of these ranges are a
+    /// ChunkError::CoordinateOutOfRange rather than a read of the wrong data. Sections whose NBT is missing or does
+    /// not match the expected layout, such as in partially generated chunks, are returned as a ChunkError instead of
+    /// panicking.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let block = chunk.get_block(5, -12, 11).unwrap();
+    /// println!("{}", block.id);
+    /// ```
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> Result<Block, ChunkError> {
+        return self.block_result(x, y, z);
+    }
+
+    /// Returns the block at an x, y, z coordinate given as a tuple, the same as get_block. x and z are still the
+    /// coordinates within the Chunk (0-15).
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - The x, y, z coordinate of the block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let pos = (5, -12, 11);
+    /// println!("{}", chunk.get_block_at(pos).unwrap().id);
+    /// ```
+    pub fn get_block_at(&self, pos: (i32, i32, i32)) -> Result<Block, ChunkError> {
+        return self.get_block(pos.0, pos.1, pos.2);
+    }
+
+    /// Returns the block that get_block returns, or the reason it could not be read.
+    fn block_result(&self, x: i32, mut y: i32, z: i32) -> Result<Block, ChunkError> {
+        let sections = self.section_list();
+        if !(0..16).contains(&x) || !(0..16).contains(&z) || !self.section_range_of(&sections).contains(&y.div_euclid(16)) {
+            return Err(ChunkError::CoordinateOutOfRange(x, y, z));
+        }
+        if self.is_pre_1_18() {
+            let section = sections.into_iter().find(|s| s.y as i32 == y.div_euclid(16));
+            return self.pre_1_18_block_result(x, y, z, section);
+        }
+        let world_y = y;
+        let section = self.get_section(y.div_euclid(16) as i8)?;
+        if section == None {
+            return Ok(Block::from_name(String::from("minecraft:air"), Some(self.block_coords(x, world_y, z)), None, None));
+        }
+        let section = section.unwrap();
+        y = y.rem_euclid(16);
+        let biomes = if let Some(Value::Compound(b)) = section.get("biomes") {
+            b
+        } else {
+            return Err(ChunkError::MissingTag("biomes"));
+        };
+        let pal = match biomes.get("palette") {
+            Some(Value::List(l)) if !l.is_empty() => l,
+            Some(_) => return Err(ChunkError::InvalidTag("palette")),
+            None => return Err(ChunkError::MissingTag("palette")),
+        };
+        // Biomes are packed the same way as block states, one index per 4x4x4 cell and never spanning two longs,
+        // but with as few bits as the palette needs. A palette with a single biome has no data.
+        let biome_id = match biomes.get("data") {
+            Some(Value::LongArray(data)) => {
+                let bits = cmp::max(bit_length(pal.len() - 1), 1) as usize;
+                let cell = ((y / 4) * 16 + (z / 4) * 4 + x / 4) as usize;
+                let per_long = 64 / bits;
+                let long = *data.get(cell / per_long).ok_or(ChunkError::InvalidTag("data"))? as u64;
+                ((long >> (cell % per_long * bits)) & ((1u64 << bits) - 1)) as usize
+            }
+            Some(_) => return Err(ChunkError::InvalidTag("data")),
+            None => 0,
+        };
+        let biome = match pal.get(biome_id) {
+            Some(Value::String(s)) => s.to_owned(),
+            _ => return Err(ChunkError::InvalidTag("palette")),
+        };
+
+        let block_states = if let Some(Value::Compound(bs)) = section.get("block_states") {
+            bs
+        } else {
+            return Ok(Block::from_name(String::from("minecraft:air"), Some(self.block_coords(x, world_y, z)), None, Some(biome)));
+        };
+
+        let palette = match block_states.get("palette") {
+            Some(Value::List(p)) if !p.is_empty() => p,
+            Some(_) => return Err(ChunkError::InvalidTag("palette")),
+            None => return Err(ChunkError::MissingTag("palette")),
+        };
+
+        // A section made of a single block has one palette entry and no data, every block in it is that entry.
+        let block = if palette.len() == 1 || block_states.get("data").is_none() {
+            &palette[0]
+        } else {
+            let states = match block_states.get("data") {
+                Some(Value::LongArray(la)) => la,
+                _ => return Err(ChunkError::InvalidTag("data")),
+            };
+            let bits = cmp::max(bit_length(palette.len() - 1), 4) as usize;
+            let index = (y * 16 * 16 + z * 16 + x) as usize;
+            // The longs are only signed because NBT has no unsigned types, the bits are read as a u64 so that
+            // shifting never drags the sign bit along.
+            let per_long = 64 / bits;
+            let long = *states.get(index / per_long).ok_or(ChunkError::InvalidTag("data"))? as u64;
+            let palette_id = ((long >> (index % per_long * bits)) & ((1u64 << bits) - 1)) as usize;
+            palette.get(palette_id).ok_or(ChunkError::InvalidTag("data"))?
+        };
+
+        let props = if let Value::Compound(c) = block {
+            match c.get("Properties") {
+                Some(Value::Compound(properties)) => Some(properties.iter().map(|f| match f.1 {
+                    Value::String(s) => Ok((f.0.to_owned(), s.to_owned())),
+                    _ => Err(ChunkError::InvalidTag("Properties")),
+                }).collect::<Result<Vec<_>, _>>()?),
+                Some(_) => return Err(ChunkError::InvalidTag("Properties")),
+                None => None,
+            }
+        } else {
+            return Err(ChunkError::InvalidTag("palette"));
+        };
+        return Block::from_palette(block, Some(self.block_coords(x, world_y, z)), props, Some(biome));
+    }
+
+    /// Returns the block at a particular x, y, z coordinate within a Chunk saved before 1.18. Biomes were stored
+    /// for the whole Chunk as numeric ids at the time, so the returned block has no biome. Chunks from before 1.13,
+    /// which have no block palette, are read as air.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate within the Chunk (0-15).
+    /// * `y` - The y coordinate of the block.
+    /// * `z` - The z coordinate within the Chunk (0-15).
+    /// * `section` - The section containing y, None if it is not stored.
+    fn pre_1_18_block_result(&self, x: i32, y: i32, z: i32, section: Option<Section>) -> Result<Block, ChunkError> {
+        let air = Block::from_name(String::from("minecraft:air"), Some(self.block_coords(x, y, z)), None, None);
+        let section = match section {
+            Some(s) => s,
+            None => return Ok(air),
+        };
+        let states = section.states()?;
+        let tag = match states.get((y.rem_euclid(16) * 256 + z * 16 + x) as usize) {
+            Some(tag) => *tag,
+            None => return Ok(air),
+        };
+        return Block::from_palette(tag, Some(self.block_coords(x, y, z)), block::palette_properties(tag), None);
+    }
+
+    /// Returns true if both Chunks contain the same blocks, ignoring everything else stored in the chunk such as
+    /// timestamps, lighting, and entities. Sections are compared block by block so two sections whose palettes
+    /// are ordered differently but describe the same blocks are still considered equal. A missing section is
+    /// treated as being entirely air.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The Chunk to compare against.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let old = Region::from_file("backup/r.0.0.mca".into()).unwrap();
+    /// let new = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// if !old.get_chunk(0, 0).unwrap().blocks_equal(&new.get_chunk(0, 0).unwrap()) {
+    ///     println!("Chunk 0, 0 changed");
+    /// }
+    /// ```
+    pub fn blocks_equal(&self, other: &Chunk) -> bool {
+        let ours = self.section_list();
+        let theirs = other.section_list();
+        let mut ys = ours.iter().chain(theirs.iter()).map(|s| s.y).collect::<Vec<i8>>();
+        ys.sort();
+        ys.dedup();
+        for y in ys {
+            let a = ours.iter().find(|s| s.y == y);
+            let b = theirs.iter().find(|s| s.y == y);
+            if !Section::blocks_equal(a, b) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /// Returns true if the Chunk contains nothing but air, either because every section's palette is only air,
+    /// because the Chunk has no sections, or because the Chunk has not started generating ('empty' status). Only
+    /// the palettes are looked at so this is cheap. Each section can be checked the same way through sections.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if chunk.is_empty() {
+    ///     println!("Void chunk");
+    /// }
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        if self.status() == Some(ChunkStatus::Empty) {
+            return true;
+        }
+        return self.section_list().iter().all(|s| s.is_empty());
+    }
+
+    /// Returns the section with the given y index, the section holding the blocks from y * 16 to y * 16 + 15. None is
+    /// returned if the section is not stored in the Chunk or is outside of the height of the world.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The y index of the section.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(section) = chunk.section(4) {
+    ///     println!("{} kinds of block between y=64 and y=79", section.palette().len());
+    /// }
+    /// ```
+    pub fn section(&self, y: i8) -> Option<Section<'_>> {
+        let sections = self.section_list();
+        if !self.section_range_of(&sections).contains(&(y as i32)) {
+            return None;
+        }
+        return sections.into_iter().find(|s| s.y == y);
+    }
+
+    /// Returns the distinct blocks in the section with the given y index, with their properties but without
+    /// coordinates or biome. Only the palette is read, none of the blocks are decoded. None is returned if the
+    /// section is not stored in the Chunk. Palette entries that cannot be read are returned as air.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The y index of the section.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```rust,no_run
     /// use simple_anvil::region::Region;
-    /// let region = Region::from_file("r.0.0.mca");
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
     /// let chunk = region.get_chunk(0, 0).unwrap();
-    /// if chunk.get_status() == "full" {
-    ///     println!("Fully Generated!");
+    /// if let Some(palette) = chunk.section_palette(-3) {
+    ///     if palette.iter().any(|block| block.id.ends_with("_ore")) {
+    ///         println!("Ore between y=-48 and y=-33");
+    ///     }
     /// }
     /// ```
-    pub fn get_status(&self) -> &String {
-        if let Value::String(s) = self.data.get("Status").unwrap() {
-            s
-        } else {
-            panic!("Value should be a string?")
-        }
+    pub fn section_palette(&self, y: i8) -> Option<Vec<Block>> {
+        return self.section(y).map(|s| s.palette());
     }
 
-    /// Returns an i64 (equivalent of Java long) of the last tick at which the chunk updated.
-    /// 
+    /// Returns an iterator over every section in the Chunk from the bottom up, including sections that only contain
+    /// air. Each section gives its y index, its palette of blocks, and whether it is empty, which is much faster
+    /// than going through the Chunk block by block when only the kinds of blocks matter.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```rust,no_run
     /// use simple_anvil::region::Region;
-    /// let region = Region::from_file("r.0.0.mca");
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
     /// let chunk = region.get_chunk(0, 0).unwrap();
-    /// println!("{}", chunk.get_last_update());
+    /// for section in chunk.sections().filter(|s| !s.is_empty()) {
+    ///     println!("{}: {} kinds of block", section.y, section.palette().len());
+    /// }
     /// ```
-    pub fn get_last_update(&self) -> &i64 {
-        if let Value::Long(l) = self.data.get("LastUpdate").unwrap() {
-            l
-        } else {
-            panic!("Value should be a i64")
-        }
+    pub fn sections(&self) -> impl Iterator<Item = Section<'_>> + '_ {
+        let mut sections = self.section_list();
+        sections.sort_by_key(|s| s.y);
+        return sections.into_iter();
     }
 
-    /// Returns a heightmap of the Chunk. If the Chunk is not fully generated then a None is returned.
-    /// 
+    /// Returns the highest `n` sections of the Chunk that contain something other than air, ordered from the top
+    /// down. This is useful for tools that only care about the surface and would rather not decode everything
+    /// underground.
+    ///
     /// # Arguments
-    /// 
-    /// * `ignore_water` - Determines which heightmap to return, if true then a heightmap that does not take into account the water is returned (OCEAN_FLOOR), if false then the water is accounted for (WORLD_SURFACE).
-    /// 
-    ///  # Examples
-    /// 
+    ///
+    /// * `n` - The maximum number of sections to return.
+    ///
+    /// # Examples
+    ///
     /// ```rust,no_run
     /// use simple_anvil::region::Region;
-    /// let region = Region::from_file("r.0.0.mca");
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
     /// let chunk = region.get_chunk(0, 0).unwrap();
-    /// let heightmap = chunk.get_heightmap(false);
+    /// for section in chunk.top_sections(2) {
+    ///     println!("{}", section.y);
+    /// }
     /// ```
-    pub fn get_heightmap(&self, ignore_water: bool) -> Option<Vec<i32>> {
-        if self.get_status() == "full" {
-            let height_maps = if let Value::Compound(hm) = self.data.get("Heightmaps").unwrap() {
-                hm
-            } else {
-                panic!()
-            };
+    pub fn top_sections(&self, n: usize) -> Vec<Section<'_>> {
+        let mut sections = self.section_list();
+        sections.sort_by(|a, b| b.y.cmp(&a.y));
+        return sections.into_iter().filter(|s| !s.is_empty()).take(n).collect();
+    }
 
-            let map = if ignore_water {
-                "OCEAN_FLOOR"
-            } else {
-                "WORLD_SURFACE"
-            };
+    /// Returns an iterator over every block in the Chunk that is not air, from the bottom section up and in y, z, x
+    /// order within each section. Sections that are missing or only contain air are skipped without being decoded,
+    /// and sections with blocks that refer to entries past the end of their palette or with biomes that cannot be
+    /// read are skipped as well. The coordinates of the blocks are the same as the ones given by get_block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// use std::collections::HashMap;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let mut counts = HashMap::new();
+    /// for block in chunk.iter_blocks() {
+    ///     *counts.entry(block.id).or_insert(0) += 1;
+    /// }
+    /// ```
+    pub fn iter_blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        let mut sections = self.section_list();
+        sections.sort_by(|a, b| a.y.cmp(&b.y));
+        let sections = sections.into_iter().filter(|section| !section.is_empty()).filter_map(|section| {
+            let biomes = section.biome_cells().ok()?;
+            return Some((section, biomes));
+        });
+        return sections.flat_map(move |(section, biomes)| {
+            section.states().unwrap_or_default().into_iter().enumerate().filter_map(move |(index, tag)| {
+                if section::is_air(tag) {
+                    return None;
+                }
+                let (x, y, z) = (index as i32 % 16, index as i32 / 256, index as i32 / 16 % 16);
+                let biome = biomes.get(((y / 4) * 16 + (z / 4) * 4 + x / 4) as usize).map(|b| b.to_string());
+                let coords = self.block_coords(x, section.y as i32 * 16 + y, z);
+                return Block::from_palette(tag, Some(coords), block::palette_properties(tag), biome).ok();
+            })
+        });
+    }
 
-            let surface = if let Value::LongArray(la) = height_maps.get(map).unwrap() {
-                la
-            } else {
-                panic!("no ocean?")
+    /// Calls a function for every block in the Chunk, from the bottom section up and in y, z, x order within each
+    /// section, including air. Sections that are not stored are skipped. The function is given the x and z
+    /// coordinates within the Chunk (0-15), the y coordinate, and the block. Each palette entry is only turned into
+    /// a Block once per section and lent to the function, so no allocation happens per block. Because of this the
+    /// blocks have no coordinates or biome. ChunkError::InvalidTag is returned if a section has a block that refers
+    /// to an entry past the end of its palette, in which case the function is not called for any block in that
+    /// section or the sections above it.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function to call for each block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let mut solid = 0;
+    /// chunk.for_each_block(|_, _, _, block| {
+    ///     if block.id != "air" {
+    ///         solid += 1;
+    ///     }
+    /// }).unwrap();
+    /// ```
+    pub fn for_each_block<F: FnMut(i32, i32, i32, &Block)>(&self, mut f: F) -> Result<(), ChunkError> {
+        let mut sections = self.section_list();
+        sections.sort_by(|a, b| a.y.cmp(&b.y));
+        for section in sections {
+            let (palette, indices) = match section.checked_palette_indices()? {
+                Some(p) => p,
+                None => continue,
             };
+            let blocks = palette.iter().map(section::palette_block).collect::<Vec<Block>>();
+            for (index, i) in indices.into_iter().enumerate() {
+                let (x, y, z) = (index as i32 % 16, index as i32 / 256, index as i32 / 16 % 16);
+                f(x, section.y as i32 * 16 + y, z, &blocks[i]);
+            }
+        }
+        return Ok(());
+    }
+
+    /// Returns the coordinates of every block in the Chunk with the given name, from the bottom section up and in y,
+    /// z, x order within each section. The coordinates are the same as the ones given by get_block. Each section is
+    /// only decoded once, and sections whose palette does not contain the block are skipped without being decoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The full name of the block, including the namespace.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// for (x, y, z) in chunk.find_blocks("minecraft:diamond_ore") {
+    ///     println!("Diamonds at {}, {}, {}", x, y, z);
+    /// }
+    /// ```
+    pub fn find_blocks(&self, name: &str) -> Vec<(i32, i32, i32)> {
+        return self.find_blocks_with_props(name, &[]);
+    }
 
-            let surface_binary: Vec<String> = surface.iter().map(|n| format!("{:b}", n)).map(|n| "0".repeat(63 - n.len()) + &n).collect();
-            let mut all = Vec::new();
-            // let mut hmm = Vec::new();
-
-            for num in surface_binary {
-                let num_chars = num.chars().collect::<Vec<_>>();
-                let mut sub_nums = num_chars.chunks(9).collect::<Vec<&[char]>>();
-                sub_nums.reverse();
-                for num in sub_nums {
-                    let test = num.iter().collect::<String>();
-                    if test != "000000000" {
-                        all.push(test.clone());
+    /// Returns the coordinates of every block in the Chunk with the given name and property values, in the same
+    /// order as find_blocks. Each listed property must be present on the block with exactly the given value, any
+    /// properties that are not listed can have any value. Sections with blocks that refer to entries past the end
+    /// of their palette are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The full name of the block, including the namespace.
+    /// * `props` - The names and values of the properties that must match.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let upright = chunk.find_blocks_with_props("minecraft:oak_log", &[("axis", "y")]);
+    /// println!("{} upright oak logs", upright.len());
+    /// ```
+    pub fn find_blocks_with_props(&self, name: &str, props: &[(&str, &str)]) -> Vec<(i32, i32, i32)> {
+        let mut sections = self.section_list();
+        sections.sort_by(|a, b| a.y.cmp(&b.y));
+        let mut found = Vec::new();
+        for section in sections {
+            let palette = match section.palette_tags() {
+                Some(p) => p,
+                None => continue,
+            };
+            let matching = palette
+                .iter()
+                .map(|tag| {
+                    let tag = match tag {
+                        Value::Compound(t) => t,
+                        _ => return false,
+                    };
+                    if !matches!(tag.get("Name"), Some(Value::String(n)) if n == name) {
+                        return false;
                     }
+                    let properties = match tag.get("Properties") {
+                        Some(Value::Compound(p)) => Some(p),
+                        _ => None,
+                    };
+                    return props.iter().all(|(key, value)| {
+                        matches!(properties.and_then(|p| p.get(*key)), Some(Value::String(v)) if v == value)
+                    });
+                })
+                .collect::<Vec<bool>>();
+            if !matching.contains(&true) {
+                continue;
+            }
+            let indices = match section.checked_palette_indices() {
+                Ok(Some((_, indices))) => indices,
+                _ => continue,
+            };
+            for (index, i) in indices.into_iter().enumerate() {
+                if matching[i] {
+                    let (x, y, z) = (index as i32 % 16, index as i32 / 256, index as i32 / 16 % 16);
+                    found.push(self.block_coords(x, section.y as i32 * 16 + y, z));
                 }
             }
+        }
+        return found;
+    }
 
-            let mut heights = Vec::new();
-
-            for num in all {
-                let n = usize::from_str_radix(num.as_str(), 2).unwrap();
-                heights.push(n as i32 - 64 - 1);
+    /// Returns the lowest block in a column that is not air, scanning up from the bottom of the world. None is
+    /// returned if the whole column is air, or if x or z is outside of the Chunk. Missing sections, and sections
+    /// with blocks that refer to entries past the end of their palette or with biomes that cannot be read, are
+    /// treated as air. The coordinates of the returned block are world coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate within the Chunk (0-15).
+    /// * `z` - The z coordinate within the Chunk (0-15).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// match chunk.lowest_block(5, 11) {
+    ///     Some(block) => println!("{} at {:?}", block.id, block.coords),
+    ///     None => println!("Hole to the void!"),
+    /// }
+    /// ```
+    pub fn lowest_block(&self, x: i32, z: i32) -> Option<Block> {
+        if !(0..16).contains(&x) || !(0..16).contains(&z) {
+            return None;
+        }
+        let mut sections = self.section_list();
+        sections.sort_by(|a, b| a.y.cmp(&b.y));
+        for section in sections {
+            let (states, biomes) = match (section.states(), section.biome_cells()) {
+                (Ok(states), Ok(biomes)) if !states.is_empty() => (states, biomes),
+                _ => continue,
+            };
+            for y in 0..16 {
+                let tag = states[(y * 256 + z * 16 + x) as usize];
+                if section::is_air(tag) {
+                    continue;
+                }
+                let biome = biomes.get(((y / 4) * 16 + (z / 4) * 4 + x / 4) as usize).map(|b| b.to_string());
+                let coords = self.block_coords(x, section.y as i32 * 16 + y, z);
+                if let Ok(block) = Block::from_palette(tag, Some(coords), block::palette_properties(tag), biome) {
+                    return Some(block);
+                }
             }
+        }
+        return None;
+    }
 
-            return Some(heights);
-        } else {
-            None
+    /// Returns the biome at a particular x, y, z coordinate within the Chunk, ie. 'minecraft:plains'. Biomes are
+    /// stored for 4x4x4 cells so every block in a cell has the same biome. None is returned if the section
+    /// containing the coordinate has not been generated, has no biomes, or has biomes that cannot be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate within the Chunk (0-15).
+    /// * `y` - The y coordinate of the block.
+    /// * `z` - The z coordinate within the Chunk (0-15).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(biome) = chunk.get_biome(5, 64, 11) {
+    ///     println!("{}", biome);
+    /// }
+    /// ```
+    pub fn get_biome(&self, x: i32, y: i32, z: i32) -> Option<String> {
+        if !(0..16).contains(&x) || !(0..16).contains(&z) {
+            return None;
         }
+        let section = self.section_at(y)?;
+        let y = y.rem_euclid(16);
+        return section.biome_cells().ok()?.get(((y / 4) * 16 + (z / 4) * 4 + x / 4) as usize).map(|b| b.to_string());
     }
 
-    /// Returns a vertical section of a Chunk
-    /// 
+    /// Returns the light level (0-15) given off by blocks at a particular x, y, z coordinate within the Chunk. None
+    /// is returned if the section containing the coordinate has no block light stored.
+    ///
     /// # Arguments
-    /// 
-    /// * `y` - The y index of the section.
-    fn get_section(&self, y: i8) -> Option<HashMap<String, Value>> {
-        if y < -4 || y > 19 {
-            panic!("Y value out of range")
+    ///
+    /// * `x` - The x coordinate within the Chunk (0-15).
+    /// * `y` - The y coordinate of the block.
+    /// * `z` - The z coordinate within the Chunk (0-15).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(light) = chunk.get_block_light(5, 64, 11) {
+    ///     println!("{}", light);
+    /// }
+    /// ```
+    pub fn get_block_light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        return self.light("BlockLight", x, y, z);
+    }
+
+    /// Returns the light level (0-15) from the sky at a particular x, y, z coordinate within the Chunk. None is
+    /// returned if the section containing the coordinate has no sky light stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate within the Chunk (0-15).
+    /// * `y` - The y coordinate of the block.
+    /// * `z` - The z coordinate within the Chunk (0-15).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// if let Some(light) = chunk.get_sky_light(5, 64, 11) {
+    ///     println!("{}", light);
+    /// }
+    /// ```
+    pub fn get_sky_light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        return self.light("SkyLight", x, y, z);
+    }
+
+    /// Returns the value of one of the light arrays at a particular coordinate, None if the section does not have
+    /// the array or the coordinate is outside of the Chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the light array, either 'SkyLight' or 'BlockLight'
+    /// * `x` - The x coordinate within the Chunk (0-15).
+    /// * `y` - The y coordinate of the block.
+    /// * `z` - The z coordinate within the Chunk (0-15).
+    fn light(&self, name: &str, x: i32, y: i32, z: i32) -> Option<u8> {
+        if !(0..16).contains(&x) || !(0..16).contains(&z) {
+            return None;
         }
-        let sections = if let Value::List(s) = self.data.get("sections").unwrap() {
-            s
-        } else {
-            panic!("Value should be a list?")
-        };
+        return self.section_at(y)?.light(name, x, y.rem_euclid(16), z);
+    }
 
-        for section in sections {
-            let section = if let Value::Compound(s) = section {
-                s
-            } else {
-                panic!("should be a compound")
-            };
-            let section_y = if let Value::Byte(sec_y) = section.get("Y").unwrap() {
-                sec_y
-            } else {
-                panic!("Failed to get y")
-            };
-            if *section_y == y {
-                let cloned = section.clone();
-                return Some(cloned);
+    /// Returns the biome of every 4x4x4 cell in the Chunk, decoding each section once. The Chunk is 4 cells wide,
+    /// 96 cells tall for the default world height, and 4 cells long, giving 1536 cells that start at the bottom of
+    /// the world. Worlds with a custom height have 64 cells for each section. Cells are ordered by
+    /// y, then z, then x, so the cell containing the block at x, y, z within the Chunk is at index
+    /// ((y - min_y) / 4) * 16 + (z / 4) * 4 + (x / 4), where min_y is the bottom of the world (usually -64). Cells in
+    /// sections that are missing, have no biomes, or have biomes that cannot be read are left as empty strings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let grid = chunk.biome_grid_3d();
+    /// // The biome of the cell holding the block at 5, 64, 11.
+    /// println!("{}", grid[((64 + 64) / 4) * 16 + (11 / 4) * 4 + 5 / 4]);
+    /// ```
+    pub fn biome_grid_3d(&self) -> Vec<String> {
+        let range = self.section_range();
+        let mut grid = vec![String::new(); range.len() * 64];
+        for section in self.section_list() {
+            if !range.contains(&(section.y as i32)) {
+                continue;
+            }
+            let index = section.y as i32 - range.start;
+            for (i, biome) in section.biome_cells().unwrap_or_default().into_iter().enumerate() {
+                grid[index as usize * 64 + i] = biome.to_string();
             }
         }
-        None
+        return grid;
     }
 
-    /// Returns the block at a particular x, y, z coordinate within a chunk. x and z should be the coordinates within the Chunk (0-15).
-    /// 
+    /// Returns the number of blocks of each type in the Chunk, keyed by the full name of the block. The state array
+    /// of each section is only decoded once, which is much faster than calling get_block for every block. Sections
+    /// without any block states are counted as air, sections that are not stored at all are not counted, and neither
+    /// are sections with blocks that refer to entries past the end of their palette.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```rust,no_run
     /// use simple_anvil::region::Region;
-    /// let region = Region::from_file("r.0.0.mca");
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
     /// let chunk = region.get_chunk(0, 0).unwrap();
-    /// let block = chunk.get_block(5, -12, 11);
-    /// println!("{}", block.id);
+    /// let counts = chunk.block_counts();
+    /// println!("{} diamond ore", counts.get("minecraft:diamond_ore").unwrap_or(&0));
     /// ```
-    pub fn get_block(&self, x: i32, mut y: i32, z: i32) -> Block {
-        let section = self.get_section(((y + 64) / 16 - 4) as i8);
-        if section == None {
-            return Block::from_name(String::from("minecraft:air"), Some((self.x as i32 * 32 + x, y, self.z as i32 * 32 + z), ), None, String::new());
+    pub fn block_counts(&self) -> HashMap<String, u64> {
+        let mut counts = HashMap::new();
+        for section in self.section_list() {
+            let states = match section.state_counts() {
+                Ok(states) => states,
+                Err(_) => continue,
+            };
+            if states.is_empty() {
+                *counts.entry(String::from("minecraft:air")).or_insert(0) += 4096;
+                continue;
+            }
+            for (tag, count) in states {
+                let name = match tag {
+                    Value::Compound(t) => match t.get("Name") {
+                        Some(Value::String(name)) => name.to_owned(),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                *counts.entry(name).or_insert(0) += count;
+            }
         }
-        let section = section.unwrap();
-        y = y.rem_euclid(16);
-        let biomes = if let Some(Value::Compound(b)) = section.get("biomes") {
-            b
-        } else {
-            panic!("Biome portion of section missing")
-        };
-        let pal = if let Value::List(l) = biomes.get("palette").unwrap() {
-            l
-        } else {
-            panic!("Biome palette missing")
-        };
-        let data_exists = biomes.get("data");
-        let biome = match data_exists {
-            Some(data) => {
-                let data = if let Value::LongArray(la) = data {
-                    la
+        return counts;
+    }
+
+    /// Returns how many blocks of a particular type have each value of one of their properties. For example the
+    /// number of rails in each orientation can be found with the block 'minecraft:rail' and the property 'shape'.
+    /// Blocks of the given type that do not have the property are not counted, and neither are sections with blocks
+    /// that refer to entries past the end of their palette.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_name` - The full name of the block, including the namespace.
+    /// * `property` - The name of the property to count the values of.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// for (shape, count) in chunk.property_distribution("minecraft:rail", "shape") {
+    ///     println!("{}: {}", shape, count);
+    /// }
+    /// ```
+    pub fn property_distribution(&self, block_name: &str, property: &str) -> HashMap<String, u64> {
+        let mut distribution = HashMap::new();
+        for section in self.section_list() {
+            for (tag, count) in section.state_counts().unwrap_or_default() {
+                let tag = if let Value::Compound(t) = tag {
+                    t
                 } else {
-                    panic!("Failed to get biome data as long array")
+                    continue;
                 };
-                let dat = data[0];
-                let bin = format!("{:b}", dat);
-                // println!("{bin}, {}", bin.len());
-                let i = bin.chars().collect::<Vec<char>>()[(((y & 0xC) << 2) | (z & 0xC) | ((x & 0xC) >> 2)) as usize].to_digit(10).unwrap();
-                if let Value::String(s) = pal[i as usize].to_owned() {
-                    s
-                } else {
-                    panic!("hah")
+                match tag.get("Name") {
+                    Some(Value::String(name)) if name == block_name => {}
+                    _ => continue,
                 }
-                
-            },
-            None => {
-                pal[0].to_string()
-            },
-        };
-        
-        let block_states = if let Some(Value::Compound(bs)) = section.get("block_states") {
-            Some(bs)
-        } else {
-            None
-        };
-        if block_states == None {
-            return Block::from_name(String::from("minecraft:air"), Some((self.x as i32 * 32 + x, y, self.z as i32 * 32 + z)), None, biome);
+                if let Some(Value::Compound(properties)) = tag.get("Properties") {
+                    if let Some(Value::String(value)) = properties.get(property) {
+                        *distribution.entry(value.to_owned()).or_insert(0) += count;
+                    }
+                }
+            }
         }
+        return distribution;
+    }
 
-        let palette = if let Value::List(p) = block_states.unwrap().get("palette").unwrap() {
-            p
-        } else {
-            panic!("Palette should be a list")
-        };
-
-        match block_states {
-            Some(bs) => {
-                let bits = cmp::max(bit_length(palette.len() - 1), 4);
-                let index = y * 16 * 16 + z * 16 + x;
-                match bs.get("data") {
-                    Some(data) => {
-                        let states = if let Value::LongArray(la) = data {
-                            la
-                        } else {
-                            panic!("something here")
-                        };
-                        let state = index as usize / (64 / bits as usize);
-                        let data = states[state];
-                        let mut d = 0;
-                        let mut modified = false;
-                        if data < 0 {
-                            d = data as u64;
-                            modified = true;
-                        }
-                        let shifted_data = (if modified { d as usize } else { data as usize }) >> (index as usize % (64 / bits as usize) * bits as usize);
-                        let palette_id = shifted_data & (2u32.pow(bits) - 1) as usize;
-                        let block = &palette[palette_id];
-                        // let props = 
-                        let props = if let Value::Compound(c) = block {
-                            match c.get("Properties") {
-                                Some(p_val) => {
-                                    let properties = if let Value::Compound(p) = p_val {
-                                        p
-                                    } else {
-                                        panic!("Properties should be a compound")
-                                    };
-                                    Some(properties.iter().map(|f| (f.0.to_owned(), if let Value::String(s) = f.1 {
-                                        s.to_owned()
-                                    } else {
-                                        panic!("Should be a string?")
-                                    })).collect::<Vec<_>>())
-  
-                                },
-                                None => None,
-                            }
-                        } else {
-                            panic!("block should be a compound")
-                        };
-                        return Block::from_palette(block, Some((self.x as i32 * 32 + x, y, self.z as i32 * 32 + z)), props, biome);
-                    },
-                    None => return Block::from_name(String::from("minecraft:air"), Some((self.x as i32 * 32 + x, y, self.z as i32 * 32 + z)), None, biome)
-                } 
-            },
-            None => {
-                return Block::from_name(String::from("minecraft:air"), Some((self.x as i32 * 32 + x, y, self.z as i32 * 32 + z)), None, biome);
-            },
-        }
-        
+    /// Returns the section containing the given y coordinate, None if the Chunk does not have that section.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The y coordinate of a block in the section.
+    pub(crate) fn section_at(&self, y: i32) -> Option<Section<'_>> {
+        let index = y.div_euclid(16);
+        return self.section_list().into_iter().find(|s| s.y as i32 == index);
+    }
+
+    /// Returns every section in the Chunk in the order they are stored. Sections without a y index are skipped.
+    /// Before 1.18 the sections are kept in the Level compound as 'Sections'.
+    pub(crate) fn section_list(&self) -> Vec<Section<'_>> {
+        let sections = match self.level_tag("sections", "Sections") {
+            Some(Value::List(s)) => s,
+            _ => return Vec::new(),
+        };
+        let data_version = self.get_data_version();
+        return sections
+            .iter()
+            .filter_map(|section| match section {
+                Value::Compound(s) => match s.get("Y") {
+                    Some(Value::Byte(y)) => Some(Section::new(*y, s, data_version)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
     }
 
     fn fill_biome_data(mut self) {
         let mut biome_data = [[""; 64]; 24].map(|e| e.map(|se| se.to_string()));
         for n in 0..24 {
-            let section = self.get_section(n).unwrap();
+            let section = self.get_section(n).unwrap().unwrap();
             let biomes = if let Some(Value::Compound(b)) = section.get("biomes") {
                 b
             } else {
@@ -321,8 +1849,118 @@ impl Chunk {
     }
 }
 
+impl fmt::Display for Chunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Chunk({}, {}, status={})", self.x, self.z, self.get_status().unwrap_or("none"))
+    }
+}
+
+impl fmt::Debug for Chunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Chunk")
+            .field("x", &self.x)
+            .field("z", &self.z)
+            .field("status", &self.get_status())
+            .field("data_version", &self.get_data_version())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "no-panic")]
+impl Chunk {
+    /// Returns the generation state of the Chunk the same way as get_status, with an error explaining why the status
+    /// could not be read in place of None.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// match chunk.try_get_status() {
+    ///     Ok(status) => println!("{}", status),
+    ///     Err(e) => println!("Bad chunk: {}", e),
+    /// }
+    /// ```
+    pub fn try_get_status(&self) -> Result<&String, ChunkError> {
+        return self.status_result();
+    }
+
+    /// Returns a heightmap of the Chunk the same way as get_heightmap, with an error explaining why the heightmap
+    /// could not be read in place of None.
+    ///
+    /// # Arguments
+    ///
+    /// * `ignore_water` - If true the OCEAN_FLOOR heightmap is returned, otherwise the WORLD_SURFACE heightmap.
+    pub fn try_get_heightmap(&self, ignore_water: bool) -> Result<Option<Vec<i32>>, ChunkError> {
+        let kind = if ignore_water {
+            HeightmapKind::OceanFloor
+        } else {
+            HeightmapKind::WorldSurface
+        };
+        return self.heightmap_result(kind);
+    }
+}
+
+/// Returns the heights from a packed heightmap, ordered by z and then x. Each stored value is the number of blocks
+/// above the bottom of the world to the first air block above the column, so one is taken off to give the y of
+/// the highest block. The number of bits used by each value is worked out from the length of the array, and arrays
+/// that are too short for values to be kept within single longs are read as values spanning longs (pre 1.16).
+///
+/// # Arguments
+///
+/// * `packed` - The packed long array of the heightmap.
+/// * `min_y` - The lowest y coordinate of the world.
+fn decode_heightmap(packed: &[i64], min_y: i32) -> Vec<i32> {
+    let fits = (1..=32u32).find(|bits| (256 + (64 / bits) as usize - 1) / (64 / bits) as usize == packed.len());
+    let (bits, spanning) = match fits {
+        Some(bits) => (bits, false),
+        None => (cmp::max(packed.len() as u32 / 4, 1), true),
+    };
+    let mask = (1u64 << bits) - 1;
+    let mut heights = Vec::with_capacity(256);
+    for n in 0..256usize {
+        let value = if spanning {
+            let bit = n * bits as usize;
+            let low = packed.get(bit / 64).map_or(0, |l| *l as u64) >> (bit % 64);
+            let high = if bit % 64 + bits as usize > 64 {
+                packed.get(bit / 64 + 1).map_or(0, |l| *l as u64) << (64 - bit % 64)
+            } else {
+                0
+            };
+            (low | high) & mask
+        } else {
+            let per_long = (64 / bits) as usize;
+            (packed.get(n / per_long).map_or(0, |l| *l as u64) >> (n % per_long * bits as usize)) & mask
+        };
+        heights.push(value as i32 + min_y - 1);
+    }
+    return heights;
+}
+
+/// Returns the first value from java.util.Random#nextInt(bound) for a Random created with the given seed.
+///
+/// # Arguments
+///
+/// * `seed` - The seed the Random is created with.
+/// * `bound` - The exclusive upper bound of the value, must be positive.
+fn java_random_next_int(seed: i64, bound: i32) -> i32 {
+    const MULTIPLIER: i64 = 0x5DEECE66D;
+    const MASK: i64 = (1 << 48) - 1;
+    let mut state = (seed ^ MULTIPLIER) & MASK;
+    loop {
+        state = state.wrapping_mul(MULTIPLIER).wrapping_add(0xB) & MASK;
+        let bits = (state >> 17) as i32;
+        let value = bits % bound;
+        // Values from the last partial range of bound are rejected so every result is equally likely.
+        if bits.wrapping_sub(value).wrapping_add(bound - 1) >= 0 {
+            return value;
+        }
+    }
+}
+
 /// Returns the bitlength of a usize value
-fn bit_length(num: usize) -> u32 {
+pub(crate) fn bit_length(num: usize) -> u32 {
     // The number of bits that the number consists of, this is an integer and we don't care about signs or leading 0's
     // 0001 and 1 have the same return value
     // I think the lowest number that could come in is -1?
@@ -348,4 +1986,244 @@ fn bin_append(a: u32, b: u32, length: Option<u32>) -> u32 {
         None => bit_length(b as usize),
     };
     return (a << length) | b
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::region::RegionBuilder;
+
+    fn stone() -> Block {
+        return Block::from_name("minecraft:stone".into(), None, None, None);
+    }
+
+    #[test]
+    fn block_coords_use_16_block_wide_chunks() {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(1, 0, 0, 64, 0, &stone()).build();
+        let chunk = region.get_chunk(1, 0).unwrap();
+        assert_eq!(chunk.get_block(0, 64, 0).unwrap().coords, Some((16, 64, 0)));
+        assert_eq!(chunk.get_block(15, 70, 15).unwrap().coords, Some((31, 70, 15)));
+    }
+
+    /// Returns a chunk whose only section is section 0, holding the given number of palette entries named
+    /// 'minecraft:block_N' and the given packed block states.
+    fn chunk_with_section(palette_len: usize, data: Vec<i64>) -> Chunk {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 64, 0, &stone()).build();
+        let mut chunk = region.get_chunk(0, 0).unwrap();
+        let palette = (0..palette_len)
+            .map(|n| {
+                let mut entry = HashMap::new();
+                entry.insert("Name".to_string(), Value::String(format!("minecraft:block_{}", n)));
+                Value::Compound(entry)
+            })
+            .collect();
+        let mut block_states = HashMap::new();
+        block_states.insert("palette".to_string(), Value::List(palette));
+        block_states.insert("data".to_string(), Value::LongArray(data));
+        let mut biomes = HashMap::new();
+        biomes.insert("palette".to_string(), Value::List(vec![Value::String("minecraft:plains".into())]));
+        let mut section = HashMap::new();
+        section.insert("Y".to_string(), Value::Byte(0));
+        section.insert("block_states".to_string(), Value::Compound(block_states));
+        section.insert("biomes".to_string(), Value::Compound(biomes));
+        chunk.data.insert("sections", Value::List(vec![Value::Compound(section)])).unwrap();
+        return chunk;
+    }
+
+    /// Returns a 1.14 chunk, with everything inside of the Level compound, storing stone in sections 0 and 5 only.
+    fn pre_1_18_chunk() -> Chunk {
+        let sections = [0i8, 5]
+            .iter()
+            .map(|y| {
+                let mut stone = HashMap::new();
+                stone.insert("Name".to_string(), Value::String("minecraft:stone".into()));
+                let mut section = HashMap::new();
+                section.insert("Y".to_string(), Value::Byte(*y));
+                section.insert("Palette".to_string(), Value::List(vec![Value::Compound(stone)]));
+                section.insert("BlockStates".to_string(), Value::LongArray(vec![0; 256]));
+                Value::Compound(section)
+            })
+            .collect();
+        let mut level = HashMap::new();
+        level.insert("xPos".to_string(), Value::Int(0));
+        level.insert("zPos".to_string(), Value::Int(0));
+        level.insert("Status".to_string(), Value::String("full".into()));
+        level.insert("Sections".to_string(), Value::List(sections));
+        let mut blob = Blob::new();
+        blob.insert("DataVersion", Value::Int(1976)).unwrap();
+        blob.insert("Level", Value::Compound(level)).unwrap();
+        let region = RegionBuilder::new("r.0.0.mca".into()).chunk_nbt(0, 0, blob).build();
+        return region.get_chunk(0, 0).unwrap();
+    }
+
+    #[test]
+    fn get_block_covers_default_height_of_pre_1_18_chunks() {
+        let chunk = pre_1_18_chunk();
+        assert_eq!(chunk.get_block(0, 85, 0).unwrap().name(), "minecraft:stone");
+        assert_eq!(chunk.get_block(0, 200, 0).unwrap().name(), "minecraft:air");
+        assert_eq!(chunk.get_block(0, 255, 0).unwrap().name(), "minecraft:air");
+        assert_eq!(chunk.get_block(0, 256, 0), Err(ChunkError::CoordinateOutOfRange(0, 256, 0)));
+        assert_eq!(chunk.get_block(0, -1, 0), Err(ChunkError::CoordinateOutOfRange(0, -1, 0)));
+        assert!(chunk.section(15).is_none());
+        assert!(chunk.section(5).is_some());
+    }
+
+    #[test]
+    fn get_block_covers_default_height_of_1_18_chunks() {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 64, 0, &stone()).build();
+        let chunk = region.get_chunk(0, 0).unwrap();
+        assert_eq!(chunk.get_block(0, 319, 0).unwrap().name(), "minecraft:air");
+        assert_eq!(chunk.get_block(0, -64, 0).unwrap().name(), "minecraft:air");
+        assert_eq!(chunk.get_block(0, 320, 0), Err(ChunkError::CoordinateOutOfRange(0, 320, 0)));
+        assert_eq!(chunk.get_block(0, -65, 0), Err(ChunkError::CoordinateOutOfRange(0, -65, 0)));
+    }
+
+    #[test]
+    fn get_block_reads_palette_index_in_sign_bit_of_long() {
+        // 16 palette entries use 4 bits each, so the 16th block of the section is stored in the top 4 bits of the
+        // first long, making the long negative when its palette index is 8 or more.
+        let mut data = vec![0i64; 256];
+        data[0] = (15u64 << 60) as i64;
+        assert!(data[0] < 0);
+        let chunk = chunk_with_section(16, data);
+        assert_eq!(chunk.get_block(15, 0, 0).unwrap().name(), "minecraft:block_15");
+        assert_eq!(chunk.get_block(14, 0, 0).unwrap().name(), "minecraft:block_0");
+    }
+
+    #[test]
+    fn palette_indices_past_the_end_of_the_palette_are_errors() {
+        // Two palette entries still use 4 bits each, so the first block can point at entry 5 which does not exist.
+        let mut data = vec![0i64; 256];
+        data[0] = 5;
+        let chunk = chunk_with_section(2, data);
+        assert_eq!(chunk.get_block(0, 0, 0), Err(ChunkError::InvalidTag("data")));
+        assert_eq!(chunk.for_each_block(|_, _, _, _| panic!("no block should be visited")), Err(ChunkError::InvalidTag("data")));
+        assert_eq!(chunk.section(0).unwrap().states(), Err(ChunkError::InvalidTag("data")));
+        assert_eq!(chunk.section(0).unwrap().state_counts(), Err(ChunkError::InvalidTag("data")));
+        assert!(chunk.block_counts().is_empty());
+        assert_eq!(chunk.iter_blocks().count(), 0);
+        assert!(chunk.find_blocks("minecraft:block_0").is_empty());
+
+        let chunk = chunk_with_section(2, vec![0i64; 256]);
+        let mut visited = 0;
+        chunk.for_each_block(|_, _, _, _| visited += 1).unwrap();
+        assert_eq!(visited, 4096);
+        assert_eq!(chunk.block_counts().get("minecraft:block_0"), Some(&4096));
+        assert_eq!(chunk.find_blocks("minecraft:block_0").len(), 4096);
+    }
+
+    #[test]
+    fn biome_indices_past_the_end_of_the_palette_are_errors() {
+        // Three biomes use 2 bits each, so the first cell can point at entry 3 which does not exist.
+        let mut chunk = chunk_with_section(1, vec![0i64; 256]);
+        let mut sections = match chunk.data.get("sections") {
+            Some(Value::List(sections)) => sections.clone(),
+            _ => unreachable!(),
+        };
+        if let Value::Compound(section) = &mut sections[0] {
+            let mut biomes = HashMap::new();
+            let names = ["minecraft:plains", "minecraft:forest", "minecraft:desert"];
+            biomes.insert("palette".to_string(), Value::List(names.iter().map(|n| Value::String(n.to_string())).collect()));
+            biomes.insert("data".to_string(), Value::LongArray(vec![3, 0]));
+            section.insert("biomes".to_string(), Value::Compound(biomes));
+        }
+        chunk.data.insert("sections", Value::List(sections)).unwrap();
+        assert_eq!(chunk.section(0).unwrap().biome_cells(), Err(ChunkError::InvalidTag("data")));
+        assert_eq!(chunk.get_biome(0, 0, 0), None);
+        assert_eq!(chunk.get_block(0, 0, 0), Err(ChunkError::InvalidTag("palette")));
+        assert_eq!(chunk.iter_blocks().count(), 0);
+        assert!(chunk.lowest_block(0, 0).is_none());
+        assert!(chunk.biome_grid_3d().iter().all(|biome| biome.is_empty()));
+    }
+
+    #[test]
+    fn bad_heightmap_and_last_update_tags_give_none() {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 64, 0, &stone()).build();
+        let mut chunk = region.get_chunk(0, 0).unwrap();
+        assert_eq!(chunk.get_heightmap_kind(HeightmapKind::WorldSurface).unwrap()[0], 64);
+        assert_eq!(chunk.get_last_update(), Some(0));
+        assert_eq!(chunk.last_update_game_days(), Some(0.0));
+        chunk.data.insert("Heightmaps", Value::Int(0)).unwrap();
+        chunk.data.insert("LastUpdate", Value::Int(0)).unwrap();
+        assert!(chunk.get_heightmap_kind(HeightmapKind::WorldSurface).is_none());
+        assert!(chunk.get_heightmap(false).is_none());
+        assert!(chunk.get_last_update().is_none());
+        assert!(chunk.last_update_game_days().is_none());
+    }
+
+    #[test]
+    fn section_palette_reads_bad_entries_as_air() {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 64, 0, &stone()).build();
+        let mut chunk = region.get_chunk(0, 0).unwrap();
+        let mut block_states = HashMap::new();
+        block_states.insert("palette".to_string(), Value::List(vec![Value::Int(7), Value::Compound(HashMap::new())]));
+        let mut section = HashMap::new();
+        section.insert("Y".to_string(), Value::Byte(0));
+        section.insert("block_states".to_string(), Value::Compound(block_states));
+        chunk.data.insert("sections", Value::List(vec![Value::Compound(section)])).unwrap();
+        let names = chunk.section_palette(0).unwrap().iter().map(|b| b.name()).collect::<Vec<String>>();
+        assert_eq!(names, vec!["minecraft:air", "minecraft:air"]);
+    }
+
+    #[test]
+    fn lowest_block_finds_block_and_rejects_columns_outside_of_chunk() {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 15, 12, 15, &stone()).build();
+        let chunk = region.get_chunk(0, 0).unwrap();
+        assert_eq!(chunk.lowest_block(15, 15).unwrap().coords, Some((15, 12, 15)));
+        assert!(chunk.lowest_block(0, 0).is_none());
+        assert!(chunk.lowest_block(16, 0).is_none());
+        assert!(chunk.lowest_block(0, -1).is_none());
+    }
+
+    #[test]
+    fn is_slime_chunk_matches_known_slime_chunks() {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 64, 0, &stone()).build();
+        let mut chunk = region.get_chunk(0, 0).unwrap();
+        let mut slime_chunks = HashMap::new();
+        slime_chunks.insert(0, vec![(-2, 0), (1, -3), (2, -3), (2, 2)]);
+        slime_chunks.insert(12345, vec![(-2, 1), (-1, 2), (0, -2), (3, 0)]);
+        for (seed, expected) in slime_chunks {
+            let mut found = Vec::new();
+            for x in -3..=3 {
+                for z in -3..=3 {
+                    chunk.data.insert("xPos", Value::Int(x)).unwrap();
+                    chunk.data.insert("zPos", Value::Int(z)).unwrap();
+                    if chunk.is_slime_chunk(seed) {
+                        found.push((x, z));
+                    }
+                }
+            }
+            assert_eq!(found, expected);
+        }
+    }
+
+    /// Packs 256 heightmap values into longs the way 1.16+ does, 9 bits each with no value spanning two longs.
+    fn pack_heightmap(values: &[u64]) -> Vec<i64> {
+        let mut packed = vec![0i64; 37];
+        for (n, value) in values.iter().enumerate() {
+            packed[n / 7] |= (value << (n % 7 * 9)) as i64;
+        }
+        return packed;
+    }
+
+    #[test]
+    fn decode_heightmap_of_flat_world_is_equal_in_every_column() {
+        // A superflat world whose top block is at y = -61 stores -61 - -64 + 1 = 4 in every column.
+        let heights = decode_heightmap(&pack_heightmap(&[4; 256]), -64);
+        assert_eq!(heights.len(), 256);
+        assert!(heights.iter().all(|h| *h == -61));
+    }
+
+    #[test]
+    fn decode_heightmap_keeps_empty_columns() {
+        let mut values = [4u64; 256];
+        values[0] = 0;
+        values[255] = 0;
+        let heights = decode_heightmap(&pack_heightmap(&values), -64);
+        assert_eq!(heights.len(), 256);
+        assert_eq!(heights[0], -65);
+        assert_eq!(heights[1], -61);
+        assert_eq!(heights[255], -65);
+    }
+}
@@ -0,0 +1,53 @@
+/// Returns the coordinates of the chunk containing a block. Chunks are 16 blocks wide, negative coordinates round
+/// down so that block -1 is in chunk -1.
+///
+/// # Arguments
+///
+/// * `x` - The x coordinate of the block.
+/// * `z` - The z coordinate of the block.
+///
+/// # Examples
+///
+/// ```rust
+/// use simple_anvil::coords::block_to_chunk;
+/// assert_eq!(block_to_chunk(17, -1), (1, -1));
+/// ```
+pub fn block_to_chunk(x: i32, z: i32) -> (i32, i32) {
+    return (x.div_euclid(16), z.div_euclid(16));
+}
+
+/// Returns the coordinates of the region containing a block. Regions are 512 blocks wide, negative coordinates
+/// round down so that block -1 is in region -1.
+///
+/// # Arguments
+///
+/// * `x` - The x coordinate of the block.
+/// * `z` - The z coordinate of the block.
+///
+/// # Examples
+///
+/// ```rust
+/// use simple_anvil::coords::block_to_region;
+/// assert_eq!(block_to_region(600, -1), (1, -1));
+/// ```
+pub fn block_to_region(x: i32, z: i32) -> (i32, i32) {
+    return (x.div_euclid(512), z.div_euclid(512));
+}
+
+/// Returns the coordinates of the region containing a chunk. Regions are 32 chunks wide, negative coordinates
+/// round down so that chunk -1 is in region -1.
+///
+/// # Arguments
+///
+/// * `chunk_x` - The x coordinate of the chunk.
+/// * `chunk_z` - The z coordinate of the chunk.
+///
+/// # Examples
+///
+/// ```rust
+/// use simple_anvil::coords::chunk_to_region;
+/// assert_eq!(chunk_to_region(33, -1), (1, -1));
+/// ```
+pub fn chunk_to_region(chunk_x: i32, chunk_z: i32) -> (i32, i32) {
+    return (chunk_x.div_euclid(32), chunk_z.div_euclid(32));
+}
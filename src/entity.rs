@@ -0,0 +1,125 @@
+use nbt::Value;
+
+/// An entity stored in a chunk, such as a mob, item, or minecart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entity {
+    /// The full name of the entity, ie. 'minecraft:zombie'
+    pub id: String,
+    /// The position of the entity in world coordinates, read from the `Pos` list of doubles.
+    pub pos: (f64, f64, f64),
+    /// The velocity of the entity in blocks per tick, read from the `Motion` list of doubles.
+    pub motion: (f64, f64, f64),
+    /// The yaw and pitch of the entity in degrees, read from the `Rotation` list of floats.
+    pub rotation: (f32, f32),
+    /// The complete compound for the entity, for any tags not covered by the other fields.
+    pub data: Value,
+}
+
+impl Entity {
+    /// Returns an Entity from a compound found in a chunk's entity list. None is returned if the compound is missing
+    /// an id or a position, or if the position is not stored as three doubles.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The compound for a single entity.
+    pub fn from_value(tag: &Value) -> Option<Entity> {
+        let compound = if let Value::Compound(c) = tag {
+            c
+        } else {
+            return None;
+        };
+        let id = if let Some(Value::String(id)) = compound.get("id") {
+            id.to_owned()
+        } else {
+            return None;
+        };
+        let pos = read_doubles(compound.get("Pos"))?;
+        // Motion and Rotation are optional for some entities, a missing value is treated as stationary.
+        let motion = read_doubles(compound.get("Motion")).unwrap_or((0.0, 0.0, 0.0));
+        let rotation = read_floats(compound.get("Rotation")).unwrap_or((0.0, 0.0));
+        return Some(Entity { id, pos, motion, rotation, data: tag.clone() });
+    }
+
+    /// Returns the block coordinates that the entity is currently inside of.
+    pub fn block_pos(&self) -> (i32, i32, i32) {
+        return (self.pos.0.floor() as i32, self.pos.1.floor() as i32, self.pos.2.floor() as i32);
+    }
+}
+
+/// Returns three doubles from a list tag. None is returned if the tag is not a list of exactly three doubles.
+///
+/// # Arguments
+///
+/// * `tag` - The list tag to read.
+fn read_doubles(tag: Option<&Value>) -> Option<(f64, f64, f64)> {
+    if let Some(Value::List(l)) = tag {
+        if let [Value::Double(a), Value::Double(b), Value::Double(c)] = l.as_slice() {
+            return Some((*a, *b, *c));
+        }
+    }
+    return None;
+}
+
+/// Returns two floats from a list tag. None is returned if the tag is not a list of exactly two floats.
+///
+/// # Arguments
+///
+/// * `tag` - The list tag to read.
+fn read_floats(tag: Option<&Value>) -> Option<(f32, f32)> {
+    if let Some(Value::List(l)) = tag {
+        if let [Value::Float(a), Value::Float(b)] = l.as_slice() {
+            return Some((*a, *b));
+        }
+    }
+    return None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    fn zombie() -> HashMap<String, Value> {
+        let mut compound = HashMap::new();
+        compound.insert("id".to_string(), Value::String("minecraft:zombie".into()));
+        compound.insert("Pos".to_string(), Value::List(vec![Value::Double(-12.5), Value::Double(64.0), Value::Double(3.25)]));
+        compound.insert("Motion".to_string(), Value::List(vec![Value::Double(0.0), Value::Double(-0.0784), Value::Double(0.0)]));
+        compound.insert("Rotation".to_string(), Value::List(vec![Value::Float(90.0), Value::Float(-15.5)]));
+        compound.insert("Health".to_string(), Value::Float(20.0));
+        return compound;
+    }
+
+    #[test]
+    fn from_value_reads_known_entity() {
+        let tag = Value::Compound(zombie());
+        let entity = Entity::from_value(&tag).unwrap();
+        assert_eq!(entity.id, "minecraft:zombie");
+        assert_eq!(entity.pos, (-12.5, 64.0, 3.25));
+        assert_eq!(entity.motion, (0.0, -0.0784, 0.0));
+        assert_eq!(entity.rotation, (90.0, -15.5));
+        assert_eq!(entity.block_pos(), (-13, 64, 3));
+        assert_eq!(entity.data, tag);
+    }
+
+    #[test]
+    fn from_value_defaults_missing_motion_and_rotation() {
+        let mut compound = zombie();
+        compound.remove("Motion");
+        compound.remove("Rotation");
+        let entity = Entity::from_value(&Value::Compound(compound)).unwrap();
+        assert_eq!(entity.motion, (0.0, 0.0, 0.0));
+        assert_eq!(entity.rotation, (0.0, 0.0));
+    }
+
+    #[test]
+    fn from_value_rejects_entity_without_id_or_position() {
+        let mut compound = zombie();
+        compound.remove("id");
+        assert!(Entity::from_value(&Value::Compound(compound)).is_none());
+        let mut compound = zombie();
+        compound.insert("Pos".to_string(), Value::List(vec![Value::Float(0.0), Value::Float(0.0), Value::Float(0.0)]));
+        assert!(Entity::from_value(&Value::Compound(compound)).is_none());
+        assert!(Entity::from_value(&Value::Int(0)).is_none());
+    }
+}
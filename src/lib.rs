@@ -8,11 +8,11 @@
 //! # Example:
 //! 
 //! ```rust,no_run
-//! use simple_anvil::region::Region
+//! use simple_anvil::region::Region;
 //! fn main() {
-//!     let region = Region::from_file("r.0.0.mca".to_string());
+//!     let region = Region::from_file("r.0.0.mca".to_string()).unwrap();
 //!     let chunk = region.get_chunk(2, 3).unwrap();
-//!     let block = chunk.get_block(5, -12, 9);
+//!     let block = chunk.get_block(5, -12, 9).unwrap();
 //! 
 //!     println!("{}", block.id);
 //! }
@@ -23,5 +23,28 @@ pub mod block;
 /// A representation of a chunk of blocks in Minecraft. 16x16x384? blocks are contained within a single chunk. This struct is used to fetch particular Blocks or to get information such as heightmaps and biomes.
 pub mod chunk;
 
+/// A 16x16x16 slice of a chunk, used to look at the blocks of a chunk without going block by block.
+pub mod section;
+
+/// An entity such as a mob or dropped item, read from a chunk's entity list.
+pub mod entity;
+
+/// The extra data of blocks such as chests and signs, read from a chunk's block entity list.
+pub mod block_entity;
+
+mod little_endian;
+
+/// Exporting chunks as meshes for viewing in 3D, only available with the `mesh` feature.
+#[cfg(feature = "mesh")]
+pub mod mesh;
+
+/// Conversions between block, chunk, and region coordinates.
+pub mod coords;
+
 /// A representation of a region file that is used to store chunk data, functionality is limited to getting particular chunks.
-pub mod region;
\ No newline at end of file
+pub mod region;
+/// A box of blocks copied out of a world, which can be saved as a Sponge schematic.
+pub mod schematic;
+
+/// A whole world made up of region files, used for reading things that span more than one region.
+pub mod world;
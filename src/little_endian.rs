@@ -0,0 +1,163 @@
+use nbt::{Blob, Value};
+
+use std::{collections::HashMap, convert::TryInto};
+
+/// The deepest that compounds and lists are allowed to nest, matching the limit used by Minecraft.
+const MAX_DEPTH: usize = 512;
+
+/// Returns a Blob read from little-endian NBT. Java Edition always writes big-endian NBT, but some third-party
+/// tools have been known to write little-endian NBT into region files. None is returned if the data is not valid
+/// little-endian NBT either, including when there is anything left over after the root compound.
+///
+/// # Arguments
+///
+/// * `data` - The uncompressed NBT data.
+pub(crate) fn read_blob(data: &[u8]) -> Option<Blob> {
+    let mut reader = Reader { data, pos: 0 };
+    if reader.u8()? != 10 {
+        return None;
+    }
+    reader.string()?;
+    let content = reader.compound(0)?;
+    if reader.pos != data.len() {
+        return None;
+    }
+    let mut blob = Blob::new();
+    for (name, value) in content {
+        blob.insert(name, value).ok()?;
+    }
+    return Some(blob);
+}
+
+/// A cursor over little-endian NBT data.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Returns the next `n` bytes, None if there are not enough left.
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let bytes = self.data.get(self.pos..end)?;
+        self.pos = end;
+        return Some(bytes);
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        return Some(self.take(1)?[0]);
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        return Some(i16::from_le_bytes(self.take(2)?.try_into().ok()?));
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        return Some(i32::from_le_bytes(self.take(4)?.try_into().ok()?));
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        return Some(i64::from_le_bytes(self.take(8)?.try_into().ok()?));
+    }
+
+    /// Returns a length prefix, None if it is negative.
+    fn len(&mut self) -> Option<usize> {
+        return self.i32()?.try_into().ok();
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = u16::from_le_bytes(self.take(2)?.try_into().ok()?) as usize;
+        return Some(String::from_utf8_lossy(self.take(len)?).into_owned());
+    }
+
+    /// Returns the entries of a compound up to and including its end tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - How deeply nested the compound is.
+    fn compound(&mut self, depth: usize) -> Option<HashMap<String, Value>> {
+        let mut entries = HashMap::new();
+        loop {
+            let id = self.u8()?;
+            if id == 0 {
+                return Some(entries);
+            }
+            let name = self.string()?;
+            entries.insert(name, self.value(id, depth + 1)?);
+        }
+    }
+
+    /// Returns the payload of a tag with the given id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The tag id of the value.
+    /// * `depth` - How deeply nested the value is.
+    fn value(&mut self, id: u8, depth: usize) -> Option<Value> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        return Some(match id {
+            1 => Value::Byte(self.u8()? as i8),
+            2 => Value::Short(self.i16()?),
+            3 => Value::Int(self.i32()?),
+            4 => Value::Long(self.i64()?),
+            5 => Value::Float(f32::from_bits(self.i32()? as u32)),
+            6 => Value::Double(f64::from_bits(self.i64()? as u64)),
+            7 => {
+                let len = self.len()?;
+                Value::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            8 => Value::String(self.string()?),
+            9 => {
+                let element = self.u8()?;
+                let len = self.len()?;
+                let mut list = Vec::new();
+                for _ in 0..len {
+                    list.push(self.value(element, depth + 1)?);
+                }
+                Value::List(list)
+            }
+            10 => Value::Compound(self.compound(depth)?),
+            11 => {
+                let len = self.len()?;
+                let mut ints = Vec::new();
+                for _ in 0..len {
+                    ints.push(self.i32()?);
+                }
+                Value::IntArray(ints)
+            }
+            12 => {
+                let len = self.len()?;
+                let mut longs = Vec::new();
+                for _ in 0..len {
+                    longs.push(self.i64()?);
+                }
+                Value::LongArray(longs)
+            }
+            _ => return None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A root compound holding the int 'hi' set to 1.
+    const BLOB: [u8; 13] = [10, 0, 0, 3, 2, 0, b'h', b'i', 1, 0, 0, 0, 0];
+
+    #[test]
+    fn read_blob_reads_a_root_compound() {
+        let blob = read_blob(&BLOB).unwrap();
+        assert_eq!(blob.get("hi"), Some(&Value::Int(1)));
+    }
+
+    #[test]
+    fn read_blob_rejects_truncated_data_and_trailing_bytes() {
+        assert!(read_blob(&BLOB[..BLOB.len() - 1]).is_none());
+        let mut data = BLOB.to_vec();
+        data.push(0);
+        assert!(read_blob(&data).is_none());
+    }
+}
@@ -0,0 +1,159 @@
+use nbt::Value;
+
+use crate::{chunk::Chunk, section};
+
+use std::{collections::HashMap, fmt::Write};
+
+/// The color used for blocks that are missing from the color table.
+const DEFAULT_COLOR: (f32, f32, f32) = (0.5, 0.5, 0.5);
+
+/// The direction and four corners of each face of a block, with the corners ordered counter-clockwise when looking
+/// at the face from outside of the block.
+const FACES: [((i32, i32, i32), [(i32, i32, i32); 4]); 6] = [
+    ((-1, 0, 0), [(0, 0, 0), (0, 0, 1), (0, 1, 1), (0, 1, 0)]),
+    ((1, 0, 0), [(1, 0, 0), (1, 1, 0), (1, 1, 1), (1, 0, 1)]),
+    ((0, -1, 0), [(0, 0, 0), (1, 0, 0), (1, 0, 1), (0, 0, 1)]),
+    ((0, 1, 0), [(0, 1, 0), (0, 1, 1), (1, 1, 1), (1, 1, 0)]),
+    ((0, 0, -1), [(0, 0, 0), (0, 1, 0), (1, 1, 0), (1, 0, 0)]),
+    ((0, 0, 1), [(0, 0, 1), (1, 0, 1), (1, 1, 1), (0, 1, 1)]),
+];
+
+impl Chunk {
+    /// Returns a Wavefront OBJ mesh of the Chunk with a cube for every block that is not air. Faces between two
+    /// blocks are left out so only the visible shell of the terrain is included, faces on the edges of the Chunk
+    /// are always included. Every block is treated as a full cube. Each vertex has a color after its position,
+    /// which most viewers support. Positions use the x and z within the Chunk and the y of the block. Sections with
+    /// blocks that refer to entries past the end of their palette are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `colors` - The color of each block as red, green, and blue from 0 to 1, keyed by the full name of the
+    /// block. Blocks that are missing are colored gray.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// use std::collections::HashMap;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let mut colors = HashMap::new();
+    /// colors.insert("minecraft:grass_block".to_string(), (0.4, 0.7, 0.3));
+    /// std::fs::write("chunk.obj", chunk.to_obj(&colors)).unwrap();
+    /// ```
+    pub fn to_obj(&self, colors: &HashMap<String, (f32, f32, f32)>) -> String {
+        let sections = self
+            .section_list()
+            .into_iter()
+            .map(|s| (s.y as i32, s.states().unwrap_or_default()))
+            .filter(|(_, states)| !states.is_empty())
+            .collect::<HashMap<i32, Vec<&Value>>>();
+        let block = |x: i32, y: i32, z: i32| -> Option<&Value> {
+            if !(0..16).contains(&x) || !(0..16).contains(&z) {
+                return None;
+            }
+            let state = sections.get(&y.div_euclid(16))?[(y.rem_euclid(16) * 256 + z * 16 + x) as usize];
+            return if section::is_air(state) { None } else { Some(state) };
+        };
+
+        let mut obj = String::new();
+        let mut vertices = 0;
+        let mut ys = sections.keys().copied().collect::<Vec<i32>>();
+        ys.sort();
+        for section_y in ys {
+            for y in section_y * 16..section_y * 16 + 16 {
+                for z in 0..16 {
+                    for x in 0..16 {
+                        let state = match block(x, y, z) {
+                            Some(s) => s,
+                            None => continue,
+                        };
+                        let color = match state {
+                            Value::Compound(c) => match c.get("Name") {
+                                Some(Value::String(name)) => *colors.get(name).unwrap_or(&DEFAULT_COLOR),
+                                _ => DEFAULT_COLOR,
+                            },
+                            _ => DEFAULT_COLOR,
+                        };
+                        for ((dx, dy, dz), corners) in FACES.iter() {
+                            let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+                            let inside = (0..16).contains(&nx) && (0..16).contains(&nz);
+                            if inside && block(nx, ny, nz).is_some() {
+                                continue;
+                            }
+                            for (cx, cy, cz) in corners.iter() {
+                                let _ = writeln!(
+                                    obj,
+                                    "v {} {} {} {} {} {}",
+                                    x + cx, y + cy, z + cz, color.0, color.1, color.2
+                                );
+                            }
+                            let _ = writeln!(obj, "f {} {} {} {}", vertices + 1, vertices + 2, vertices + 3, vertices + 4);
+                            vertices += 4;
+                        }
+                    }
+                }
+            }
+        }
+        return obj;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::Block, region::RegionBuilder};
+
+    fn stone() -> Block {
+        return Block::from_name("minecraft:stone".into(), None, None, None);
+    }
+
+    /// Returns the corners of every face in an OBJ mesh, read from the vertices that each face refers to.
+    fn faces(obj: &str) -> Vec<Vec<(i32, i32, i32)>> {
+        let vertices = obj
+            .lines()
+            .filter_map(|line| line.strip_prefix("v "))
+            .map(|line| {
+                let v = line.split(' ').take(3).map(|n| n.parse::<i32>().unwrap()).collect::<Vec<i32>>();
+                (v[0], v[1], v[2])
+            })
+            .collect::<Vec<(i32, i32, i32)>>();
+        return obj
+            .lines()
+            .filter_map(|line| line.strip_prefix("f "))
+            .map(|line| line.split(' ').map(|n| vertices[n.parse::<usize>().unwrap() - 1]).collect())
+            .collect();
+    }
+
+    #[test]
+    fn to_obj_leaves_out_faces_between_blocks() {
+        let region = RegionBuilder::new("r.0.0.mca".into())
+            .set_block(0, 0, 5, 64, 5, &stone())
+            .set_block(0, 0, 6, 64, 5, &stone())
+            .build();
+        let mut colors = HashMap::new();
+        colors.insert("minecraft:stone".to_string(), (0.25, 0.5, 0.75));
+        let obj = region.get_chunk(0, 0).unwrap().to_obj(&colors);
+        let faces = faces(&obj);
+        assert_eq!(faces.len(), 10);
+        assert!(!faces.iter().any(|face| face.iter().all(|(x, _, _)| *x == 6)));
+        assert_eq!(faces.iter().filter(|face| face.iter().all(|(x, _, _)| *x == 5)).count(), 1);
+        assert_eq!(faces.iter().filter(|face| face.iter().all(|(x, _, _)| *x == 7)).count(), 1);
+        assert!(obj.lines().filter(|line| line.starts_with("v ")).all(|line| line.ends_with(" 0.25 0.5 0.75")));
+    }
+
+    #[test]
+    fn to_obj_keeps_faces_next_to_air_and_on_the_chunk_edge() {
+        // The block in the next chunk is touching the first one, but each chunk is meshed on its own.
+        let region = RegionBuilder::new("r.0.0.mca".into())
+            .set_block(0, 0, 15, 64, 0, &stone())
+            .set_block(1, 0, 0, 64, 0, &stone())
+            .build();
+        let faces = faces(&region.get_chunk(0, 0).unwrap().to_obj(&HashMap::new()));
+        assert_eq!(faces.len(), 6);
+        assert_eq!(faces.iter().filter(|face| face.iter().all(|(x, _, _)| *x == 16)).count(), 1);
+        assert_eq!(faces.iter().filter(|face| face.iter().all(|(_, _, z)| *z == 0)).count(), 1);
+        assert_eq!(faces.iter().filter(|face| face.iter().all(|(_, y, _)| *y == 65)).count(), 1);
+    }
+}
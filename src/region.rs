@@ -1,84 +1,339 @@
-use nbt::Blob;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use nbt::{Blob, Value};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
-use crate::{chunk::Chunk, block::Block};
+use crate::{chunk::{self, Chunk}, block::Block, coords, entity::Entity, little_endian};
 
 use std::{
-    array::TryFromSliceError,
-    cell::Cell,
-    convert::TryInto,
-    fs,
-    marker::{self, PhantomData},
-    path::Path,
+    cmp,
+    collections::{HashMap, HashSet},
+    error, fmt,
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Mutex,
 };
 
+/// The errors that can occur while reading a region file.
+#[derive(Debug)]
+pub enum RegionError {
+    /// The location entry for a chunk points into the two header sectors at the start of the file, this only
+    /// happens when the region file is corrupt.
+    HeaderOverlap {
+        /// The x coordinate of the chunk within the region.
+        chunk_x: u32,
+        /// The z coordinate of the chunk within the region.
+        chunk_z: u32,
+        /// The sector that the chunk claims to start at.
+        sector: u32,
+    },
+    /// The data for a chunk runs past the end of the region file, or past the sectors set aside for it.
+    Truncated {
+        /// The x coordinate of the chunk within the region.
+        chunk_x: u32,
+        /// The z coordinate of the chunk within the region.
+        chunk_z: u32,
+    },
+    /// The length stored for a chunk is 0, which is too short to even hold the compression type.
+    ZeroLength {
+        /// The x coordinate of the chunk within the region.
+        chunk_x: u32,
+        /// The z coordinate of the chunk within the region.
+        chunk_z: u32,
+    },
+    /// The chunk is too large to fit in the region file and is stored in an external .mcc file, which cannot be
+    /// found because the directory or the position of the region is not known. This happens for regions that
+    /// were not read from a region file with a standard name, ie. 'r.0.0.mca'.
+    ExternalChunk {
+        /// The x coordinate of the chunk within the region.
+        chunk_x: u32,
+        /// The z coordinate of the chunk within the region.
+        chunk_z: u32,
+    },
+    /// The chunk data could not be decompressed or is not valid NBT.
+    Nbt(nbt::Error),
+    /// The chunk uses a compression type that cannot be read, either LZ4 (type 4) without the 'lz4' feature or a
+    /// type that Minecraft does not define.
+    UnsupportedCompression(u8),
+    /// The region file does not exist.
+    NotFound,
+    /// The region file is too short to hold the location and timestamp tables, which take up the first 8192 bytes.
+    /// This usually means the file was cut off while it was being written.
+    TooShort {
+        /// The length of the region file in bytes.
+        len: usize,
+    },
+    /// Reading from the underlying file failed.
+    Io(io::Error),
+    /// The area asked for is wider or longer than the 512 blocks covered by a Region.
+    AreaTooLarge {
+        /// The number of columns along the x axis.
+        width: u64,
+        /// The number of columns along the z axis.
+        length: u64,
+    },
+}
+
+impl fmt::Display for RegionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegionError::HeaderOverlap { chunk_x, chunk_z, sector } => write!(
+                f,
+                "chunk {}, {} points at sector {} which is inside the region header",
+                chunk_x, chunk_z, sector
+            ),
+            RegionError::Truncated { chunk_x, chunk_z } => {
+                write!(f, "chunk {}, {} runs past the end of the region file", chunk_x, chunk_z)
+            }
+            RegionError::ZeroLength { chunk_x, chunk_z } => write!(f, "chunk {}, {} has a length of 0", chunk_x, chunk_z),
+            RegionError::ExternalChunk { chunk_x, chunk_z } => {
+                write!(f, "chunk {}, {} is stored in a .mcc file that cannot be located", chunk_x, chunk_z)
+            }
+            RegionError::Nbt(e) => write!(f, "failed to read chunk data: {}", e),
+            RegionError::UnsupportedCompression(compression) => {
+                write!(f, "chunk uses unsupported compression type {}", compression)
+            }
+            RegionError::NotFound => write!(f, "region file not found"),
+            RegionError::TooShort { len } => {
+                write!(f, "region file is {} bytes, too short to hold the 8192 byte header", len)
+            }
+            RegionError::Io(e) => write!(f, "failed to read region: {}", e),
+            RegionError::AreaTooLarge { width, length } => {
+                write!(f, "an area of {}x{} columns is larger than the 512x512 columns of a region", width, length)
+            }
+        }
+    }
+}
+
+impl error::Error for RegionError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            RegionError::Nbt(e) => Some(e),
+            RegionError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The two tables at the start of a region file. Both tables have an entry for each of the 32x32 chunks in the
+/// region, the entry for the chunk at x, z is at index x + z * 32.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionHeader {
+    /// The sector offset and sector count of each chunk, (0, 0) for chunks that are not present. Sectors are 4 KiB.
+    pub locations: [(u32, u32); 1024],
+    /// The time that each chunk was last saved, in seconds since the Unix epoch.
+    pub timestamps: [u32; 1024],
+}
+
+impl RegionHeader {
+    /// Returns the header read from the start of a region file. Entries past the end of the data are left as 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The region data, starting with the location table.
+    fn from_bytes(data: &[u8]) -> RegionHeader {
+        let mut header = RegionHeader { locations: [(0, 0); 1024], timestamps: [0; 1024] };
+        for n in 0..1024 {
+            if let Some(entry) = data.get(n * 4..n * 4 + 4) {
+                header.locations[n] = (from_be_3_bytes([entry[0], entry[1], entry[2]]), entry[3] as u32);
+            }
+            if let Some(entry) = data.get(4096 + n * 4..4096 + n * 4 + 4) {
+                header.timestamps[n] = u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]);
+            }
+        }
+        return header;
+    }
+}
+
 /// Low level storage of region file contents.
-#[derive(Clone)]
-pub struct Region<'a> {
+pub struct Region {
     /// Vector containing all of the data in bytes.
     data: Vec<u8>,
-    /// I don't remember what this was for.
-    _marker: marker::PhantomData<Cell<&'a ()>>,
     /// The name of the file that the region was derived from.
     pub filename: String,
+    /// The directory containing the region file, used to find chunks stored in external .mcc files. None if the
+    /// region was not read from a file.
+    directory: Option<PathBuf>,
+    /// Chunks that have already been parsed, keyed by their coordinates within the region.
+    cache: Mutex<HashMap<(u32, u32), Chunk>>,
 }
 
-impl<'a> Region<'a> {
-    /// Returns the header size and returns an offset for a particular chunk.
-    /// 
+impl Clone for Region {
+    fn clone(&self) -> Self {
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        return Region {
+            data: self.data.clone(),
+            filename: self.filename.clone(),
+            directory: self.directory.clone(),
+            cache: Mutex::new(cache),
+        };
+    }
+}
+
+// Fails to compile if Region stops being safe to share between threads.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Region>();
+};
+
+impl Region {
+    /// Returns the location and timestamp tables from the start of the region file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let header = region.header();
+    /// let present = header.locations.iter().filter(|l| **l != (0, 0)).count();
+    /// println!("{} chunks", present);
+    /// ```
+    pub fn header(&self) -> RegionHeader {
+        return RegionHeader::from_bytes(&self.data);
+    }
+
+    /// Returns true if the region has a chunk at the given coordinates. Only the location table is looked at, the
+    /// chunk is not read or parsed, so this is much cheaper than get_chunk.
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `chunk_x` - The x coordinate of the particular chunk
     /// * `chunk_z` - The z coordinate of the particular chunk
-    fn header_offset(&self, chunk_x: u32, chunk_z: u32) -> u32 {
-        return 4 * (chunk_x % 32 + chunk_z % 32 * 32);
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let populated = (0..1024).filter(|i| region.has_chunk(i % 32, i / 32)).count();
+    /// println!("{} chunks", populated);
+    /// ```
+    pub fn has_chunk(&self, chunk_x: u32, chunk_z: u32) -> bool {
+        return location_entry(&self.data, header_offset(chunk_x, chunk_z) as usize) != (0, 0);
+    }
+
+    /// Returns the coordinates within the Region of every chunk that is present, in the order they appear in the
+    /// location table. Only the location table is looked at, no chunks are read or parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// for (x, z) in region.populated_chunks() {
+    ///     println!("Chunk {}, {}", x, z);
+    /// }
+    /// ```
+    pub fn populated_chunks(&self) -> Vec<(u32, u32)> {
+        return (0..1024u32)
+            .map(|index| (index % 32, index / 32))
+            .filter(|(chunk_x, chunk_z)| self.has_chunk(*chunk_x, *chunk_z))
+            .collect();
     }
 
-    /// Returns the location where a particular chunk is found.
+    /// Returns the time that a chunk was last saved, in seconds since the Unix epoch. None is returned if the chunk
+    /// is not present. The timestamp is read from the second sector of the header without reading the chunk, so
+    /// this is a cheap way to skip chunks that have not changed since they were last processed.
     ///
     /// # Arguments
-    /// 
+    ///
     /// * `chunk_x` - The x coordinate of the particular chunk
     /// * `chunk_z` - The z coordinate of the particular chunk
-    fn chunk_location(&self, chunk_x: u32, chunk_z: u32) -> (u32, u32) {
-        let b_off = self.header_offset(chunk_x, chunk_z) as usize;
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// if let Some(timestamp) = region.get_timestamp(3, 7) {
+    ///     println!("Last saved at {}", timestamp);
+    /// }
+    /// ```
+    pub fn get_timestamp(&self, chunk_x: u32, chunk_z: u32) -> Option<u32> {
+        if !self.has_chunk(chunk_x, chunk_z) {
+            return None;
+        }
+        let offset = header_offset(chunk_x, chunk_z) as usize;
+        let entry = self.data.get(4096 + offset..4096 + offset + 4)?;
+        return Some(u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]));
+    }
 
-        let temp_range = &self.data[b_off..b_off + 3];
-        let temp: [u8; 3] = temp_range
-            .try_into()
-            .expect("Failed to convert slice into array.");
+    /// Returns a Blob of all the data for a particular chunk. None is returned if the chunk is absent or if its
+    /// location is corrupt, use try_chunk_data to tell the two apart. Chunks compressed with gzip (type 1) or zlib
+    /// (type 2) and uncompressed chunks (type 3) are always supported, and chunks compressed with LZ4 (type 4) are
+    /// supported with the 'lz4' feature. Chunks using any other compression are returned as None, and as
+    /// RegionError::UnsupportedCompression from try_chunk_data. Chunks too large for the region file are read from
+    /// their .mcc file next to the region file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `chunk_x` - The x coordinate of the particular chunk
+    /// * `chunk_z` - The z coordinate of the particular chunk
+    pub fn chunk_data(&self, chunk_x: u32, chunk_z: u32) -> Option<Box<Blob>> {
+        return self.try_chunk_data(chunk_x, chunk_z).ok().flatten();
+    }
 
-        let off = from_be_3_bytes(temp);
-        let sectors = self.data[b_off as usize + 3];
-        return (off, sectors as u32);
+    /// Returns a Blob of all the data for a particular chunk, or None if the chunk has not been generated. An error
+    /// is returned if the chunk's location points inside the header, as the first two sectors of a region file
+    /// always hold the location and timestamp tables and can never contain chunk data.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `chunk_x` - The x coordinate of the particular chunk
+    /// * `chunk_z` - The z coordinate of the particular chunk
+    pub fn try_chunk_data(&self, chunk_x: u32, chunk_z: u32) -> Result<Option<Box<Blob>>, RegionError> {
+        return Ok(self.read_chunk(chunk_x, chunk_z)?.map(|(data, _)| data));
     }
 
-    /// Returns a Blob of all the data for a particular chunk. 
+    /// Returns a Blob of all the data for a particular chunk along with whether the NBT had to be read as
+    /// little-endian. Region files should always contain big-endian NBT, but some third-party tools write
+    /// little-endian NBT by mistake so that is tried when the big-endian read fails.
     /// 
     /// # Arguments
     /// 
     /// * `chunk_x` - The x coordinate of the particular chunk
     /// * `chunk_z` - The z coordinate of the particular chunk
-    pub fn chunk_data(&self, chunk_x: u32, chunk_z: u32) -> Option<Box<Blob>> {
-        let off = self.chunk_location(chunk_x, chunk_z);
-        if off == (0, 0) {
-            return None;
-        }
-        let off: u32 = off.0 as u32 * 4096;
+    pub(crate) fn read_chunk(&self, chunk_x: u32, chunk_z: u32) -> Result<Option<(Box<Blob>, bool)>, RegionError> {
+        return read_chunk_from(&self.data, self.external_location(), chunk_x, chunk_z);
+    }
 
-        let temp: Result<[u8; 4], TryFromSliceError> =
-            self.data[off as usize..off as usize + 4].try_into();
-        let length = u32::from_be_bytes(temp.unwrap());
-        let compression = self.data[off as usize + 4];
-        if compression == 1 {
-            return None;
-        }
-        let compressed_data: Vec<u8> =
-            self.data[off as usize + 5..off as usize + 5 + length as usize - 1].into();
-        let data = Box::new(Blob::from_zlib_reader(&mut compressed_data.as_slice()).unwrap());
-        return Some(data);
+    /// Returns a handle to a region file that only reads the header up front, chunks are read from the file as they
+    /// are requested. This uses far less memory than from_file when only a few chunks of many regions are needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the region file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::open_lazy("r.0.0.mca").unwrap();
+    /// let chunk = region.get_chunk(11, 2).unwrap();
+    /// ```
+    pub fn open_lazy<P: AsRef<Path>>(path: P) -> io::Result<LazyRegion> {
+        let path = path.as_ref();
+        let mut file = File::open(path)?;
+        let mut header = vec![0; 8192];
+        file.read_exact(&mut header)?;
+        let filename = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => String::new(),
+        };
+        let directory = path.parent().map(Path::to_path_buf);
+        return Ok(LazyRegion { file: Mutex::new(file), header, filename, directory });
     }
 
-    /// Returns a region using a region(.mca) file
+    /// Returns a region using a region(.mca) file. RegionError::NotFound is returned if the file does not exist, and
+    /// RegionError::TooShort if the file is too short to hold the header.
     /// 
     /// # Arguments
     /// 
@@ -89,18 +344,188 @@ impl<'a> Region<'a> {
     /// ```rust,no_run
     /// use simple_anvil::region::Region;
     /// 
-    /// let region = Region::from_file("r.0.0.mca".into());
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
     /// ```
-    pub fn from_file(file: String) -> Region<'a> {
+    pub fn from_file(file: String) -> Result<Region, RegionError> {
         let f = Path::new(&file);
-        return Region {
-            data: fs::read(file.clone()).unwrap(),
-            _marker: PhantomData,
-            filename: f.file_name().unwrap().to_str().unwrap().to_string(),
+        let filename = match f.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => {
+                return Err(RegionError::Io(io::Error::new(io::ErrorKind::InvalidInput, "path does not name a file")));
+            }
+        };
+        let data = match fs::read(&file) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(RegionError::NotFound),
+            Err(e) => return Err(RegionError::Io(e)),
         };
+        if data.len() < 8192 {
+            return Err(RegionError::TooShort { len: data.len() });
+        }
+        let mut region = Region::from_bytes(data, filename);
+        region.directory = f.parent().map(Path::to_path_buf);
+        return Ok(region);
+    }
+
+    /// Returns every region in a directory, such as the 'region' directory of a world. Only files named like
+    /// 'r.X.Z.mca' are read, other files are skipped, as are region files too short to hold any chunks. The regions
+    /// are sorted by filename.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory containing the region files.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// use std::path::Path;
+    ///
+    /// for region in Region::open_dir(Path::new("world/region")).unwrap() {
+    ///     println!("{:?}", region.coords());
+    /// }
+    /// ```
+    pub fn open_dir(path: &Path) -> io::Result<Vec<Region>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if parse_region_name(&name).is_some() && entry.file_type()?.is_file() {
+                files.push((name, entry.path()));
+            }
+        }
+        files.sort();
+        let mut regions = Vec::new();
+        for (_, file) in files {
+            match Region::from_file(file.to_string_lossy().to_string()) {
+                Ok(region) => regions.push(region),
+                Err(RegionError::TooShort { .. }) => continue,
+                Err(RegionError::Io(e)) => return Err(e),
+                Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+            }
+        }
+        return Ok(regions);
+    }
+
+    /// Returns a region from a particular region file, panicking if the file cannot be read. This is how from_file
+    /// behaved before it returned a Result.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file name and relative path of the region file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file_unchecked("r.0.0.mca".into());
+    /// ```
+    pub fn from_file_unchecked(file: String) -> Region {
+        return Region::from_file(file).unwrap_or_else(|e| panic!("{}", e));
     }
 
-    /// Returns a Chunk contained within the Region. A region file contains 32x32 chunks.
+    /// Returns a region from region data that is already in memory, such as a file read out of an archive. The data
+    /// is not checked, chunks whose location entries are cut off are read as not present.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The contents of the region file.
+    /// * `filename` - The name of the region, ie. 'r.0.0.mca'
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let data = std::fs::read("r.0.0.mca").unwrap();
+    /// let region = Region::from_bytes(data, "r.0.0.mca".into());
+    /// ```
+    pub fn from_bytes(data: Vec<u8>, filename: String) -> Region {
+        return Region { data, filename, directory: None, cache: Mutex::new(HashMap::new()) };
+    }
+
+    /// Returns a region read from any source of bytes, such as a network stream or a decompressor. The whole
+    /// stream is read before the region is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the region data.
+    /// * `filename` - The name of the region, ie. 'r.0.0.mca'
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// use std::{fs::File, io::BufReader};
+    ///
+    /// let mut reader = BufReader::new(File::open("r.0.0.mca").unwrap());
+    /// let region = Region::from_reader(&mut reader, "r.0.0.mca".into()).unwrap();
+    /// ```
+    pub fn from_reader<R: Read>(reader: &mut R, filename: String) -> io::Result<Region> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        return Ok(Region::from_bytes(data, filename));
+    }
+
+    /// Returns a region from a region file that has been gzipped as a whole, such as 'r.0.0.mca.gz' from a backup.
+    /// The file is decompressed in memory and then read like any other region. A trailing '.gz' is dropped from the
+    /// filename so that the region coordinates can still be found from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the gzipped region file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_gzip_file("backup/r.0.0.mca.gz").unwrap();
+    /// ```
+    pub fn from_gzip_file<P: AsRef<Path>>(path: P) -> io::Result<Region> {
+        let path = path.as_ref();
+        let mut data = Vec::new();
+        GzDecoder::new(File::open(path)?).read_to_end(&mut data)?;
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let filename = name.strip_suffix(".gz").map(str::to_string).unwrap_or(name);
+        let mut region = Region::from_bytes(data, filename);
+        region.directory = path.parent().map(Path::to_path_buf);
+        return Ok(region);
+    }
+
+    /// Returns a region that is embedded at an offset within a larger file, such as a custom archive format.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The open file containing the region.
+    /// * `offset` - The byte offset in the file at which the region starts.
+    /// * `len` - The length of the region in bytes.
+    /// * `filename` - The name of the region, ie. 'r.0.0.mca'
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("world.archive").unwrap();
+    /// let region = Region::from_file_at(file, 1024, 65536, "r.0.0.mca".into()).unwrap();
+    /// ```
+    pub fn from_file_at(mut file: File, offset: u64, len: u64, filename: String) -> io::Result<Region> {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut data = Vec::new();
+        file.take(len).read_to_end(&mut data)?;
+        if (data.len() as u64) < len {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "file ended before the end of the region"));
+        }
+        return Ok(Region::from_bytes(data, filename));
+    }
+
+    /// Returns a Chunk contained within the Region. A region file contains 32x32 chunks. Chunks are kept once they
+    /// have been parsed, so asking for the same chunk again returns a copy without decompressing it again. Methods
+    /// that go over the whole Region, such as chunks, read each chunk without keeping it so that a scan does not
+    /// leave every chunk of the Region in memory.
     /// 
     /// # Arguments
     /// 
@@ -112,14 +537,59 @@ impl<'a> Region<'a> {
     /// ```rust,no_run
     /// use simple_anvil::region::Region;
     /// 
-    /// let region = Region::from_file("r.0.0.mca".into());
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
     /// let chunk = region.get_chunk(11, 2).unwrap();
     /// ```
     pub fn get_chunk(&self, chunk_x: u32, chunk_z: u32) -> Option<Chunk> {
-        return Chunk::from_region(self, chunk_x, chunk_z);
+        if let Some(chunk) = self.cache.lock().unwrap_or_else(|e| e.into_inner()).get(&(chunk_x, chunk_z)) {
+            return Some(chunk.clone());
+        }
+        let chunk = Chunk::from_region(self, chunk_x, chunk_z)?;
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).insert((chunk_x, chunk_z), chunk.clone());
+        return Some(chunk);
+    }
+
+    /// Returns a Chunk contained within the Region from absolute chunk coordinates, which can be negative. The
+    /// region is 32 chunks wide so the coordinates wrap every 32 chunks, which means absolute coordinates find the
+    /// right chunk within the region that contains them.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_x` - The absolute x coordinate of the chunk
+    /// * `chunk_z` - The absolute z coordinate of the chunk
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.-1.-1.mca".into()).unwrap();
+    /// let chunk = region.get_chunk_at(-5, -30).unwrap();
+    /// ```
+    pub fn get_chunk_at(&self, chunk_x: i32, chunk_z: i32) -> Option<Chunk> {
+        return self.get_chunk(chunk_x.rem_euclid(32) as u32, chunk_z.rem_euclid(32) as u32);
+    }
+
+    /// Forgets every chunk that has been parsed, so that long running tools can limit how much memory the Region
+    /// holds on to. Chunks are parsed again the next time they are asked for.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(11, 2).unwrap();
+    /// region.clear_cache();
+    /// ```
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).clear();
     }
 
-    /// Returns a Block contained within the Region. None is returned if the Chunk the Block would exist in is not fully generated.
+    /// Returns a Block contained within the Region. None is returned if the Chunk the Block would exist in is not fully generated
+    /// or the Block cannot be read from it.
+    /// Chunks are 16 blocks wide and the region is 32 chunks wide, so x and z wrap every 512 blocks, which means
+    /// world coordinates, including negative ones, find the right block within the region that contains them.
     /// 
     /// # Arguments
     /// 
@@ -132,29 +602,1194 @@ impl<'a> Region<'a> {
     /// ```rust,no_run
     /// use simple_anvil::region::Region;
     /// 
-    /// let region = Region::from_file("r.0.0.mca".into());
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
     /// println!("{}", region.get_block(20, 56, 45).unwrap().id);
     /// ```
     pub fn get_block(&self, x: i32, y: i32, z: i32) -> Option<Block> {
-        let chunk = self.get_chunk((x / 32) as u32, (z / 32) as u32).unwrap();
-        return match chunk.get_status().as_str() {
-            "full" => {
-                Some(chunk.get_block(x % 32, y, z % 32))
-            },
-            _ => None,
+        let (chunk_x, chunk_z) = coords::block_to_chunk(x, z);
+        let chunk = self.get_chunk_at(chunk_x, chunk_z)?;
+        if chunk.is_fully_generated() {
+            return chunk.get_block(x.rem_euclid(16), y, z.rem_euclid(16)).ok();
         }
+        return None;
     }
-}
 
-/// Returns an unsigned int from three bytes. This might not be needed anymore.
-/// 
-/// # Arguments
-/// 
-/// * `bytes` - The bytes to be converted into u32
-fn from_be_3_bytes(bytes: [u8; 3]) -> u32 {
-    let mut temp: [u8; 4] = [0; 4];
-    for n in 0..bytes.len() {
-        temp[n + 1] = bytes[n];
+    /// Returns the Block at absolute world coordinates, the same as get_block except that coordinates outside of
+    /// this Region's 512x512 footprint return None instead of wrapping around into the wrong chunk. The position of
+    /// the Region is parsed from its filename, so None is always returned for a Region with a non-standard filename.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The world x coordinate of the block
+    /// * `y` - The world y coordinate of the block
+    /// * `z` - The world z coordinate of the block
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.-1.0.mca".into()).unwrap();
+    /// assert!(region.get_block_world(20, 56, 45).is_none());
+    /// if let Some(block) = region.get_block_world(-20, 56, 45) {
+    ///     println!("{}", block.id);
+    /// }
+    /// ```
+    pub fn get_block_world(&self, x: i32, y: i32, z: i32) -> Option<Block> {
+        if self.coords()? != coords::block_to_region(x, z) {
+            return None;
+        }
+        return self.get_block(x, y, z);
+    }
+
+    /// Returns the biome at a block within the Region, ie. 'minecraft:plains'. Biomes are stored for 4x4x4 cells, so
+    /// every block in a cell gives the same biome. The coordinates wrap every 512 blocks the same way as get_block,
+    /// so world coordinates, including negative ones, find the right chunk within the region that contains them.
+    /// None is returned if the chunk or section has not been generated far enough to have biomes.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate of the block
+    /// * `y` - The y coordinate of the block
+    /// * `z` - The z coordinate of the block
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// if let Some(biome) = region.get_biome(20, 64, 45) {
+    ///     println!("{}", biome);
+    /// }
+    /// ```
+    pub fn get_biome(&self, x: i32, y: i32, z: i32) -> Option<String> {
+        let (chunk_x, chunk_z) = coords::block_to_chunk(x, z);
+        let chunk = self.get_chunk_at(chunk_x, chunk_z)?;
+        return chunk.get_biome(x.rem_euclid(16), y, z.rem_euclid(16));
+    }
+
+    /// Returns an iterator over every chunk present in the Region, in the order they appear in the location table.
+    /// Chunks whose location entry is empty are skipped without being read, as are chunks that cannot be read.
+    /// Chunks are not cached, use get_chunk for chunks that will be asked for again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// for chunk in region.chunks() {
+    ///     println!("Chunk {}, {} is {}", chunk.x, chunk.z, chunk.get_status().unwrap_or("unknown"));
+    /// }
+    /// ```
+    pub fn chunks(&self) -> Box<dyn Iterator<Item = Chunk> + '_> {
+        let locations = self.header().locations;
+        return Box::new(
+            (0..1024u32)
+                .filter(move |index| locations[*index as usize] != (0, 0))
+                .filter_map(move |index| Chunk::from_region(self, index % 32, index / 32)),
+        );
+    }
+
+    /// Returns a parallel iterator over every chunk present in the Region, parsing the chunks across all of the
+    /// threads in the rayon thread pool. Chunks are yielded in no particular order and are not cached. Only
+    /// available with the 'rayon' feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use rayon::prelude::*;
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let full = region.par_chunks().filter(|chunk| chunk.is_fully_generated()).count();
+    /// println!("{} fully generated chunks", full);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_chunks(&self) -> impl ParallelIterator<Item = Chunk> + '_ {
+        let data = &self.data;
+        let external = self.external_location();
+        return (0..1024u32).into_par_iter().filter_map(move |index| {
+            let (chunk_x, chunk_z) = (index % 32, index / 32);
+            let (blob, little_endian) = read_chunk_from(data, external, chunk_x, chunk_z).ok().flatten()?;
+            return Some(Chunk::from_data(blob, chunk_x, chunk_z, little_endian));
+        });
+    }
+
+    /// Returns an iterator over every chunk present in the Region along with the absolute chunk coordinates of
+    /// the chunk in the world. The position of the region is parsed from the filename (r.X.Z.mca), if the filename
+    /// does not follow that pattern then the xPos and zPos stored in each chunk are used instead. Chunks that have
+    /// not been generated are skipped. Chunks are not cached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.-1.2.mca".into()).unwrap();
+    /// for ((x, z), chunk) in region.chunks_with_coords() {
+    ///     println!("Chunk at {}, {} is {}", x, z, chunk.get_status().unwrap_or("unknown"));
+    /// }
+    /// ```
+    pub fn chunks_with_coords(&self) -> Box<dyn Iterator<Item = ((i32, i32), Chunk)> + '_> {
+        let origin = self.coords();
+        return Box::new((0..1024u32).filter_map(move |index| {
+            let (chunk_x, chunk_z) = (index % 32, index / 32);
+            let chunk = Chunk::from_region(self, chunk_x, chunk_z)?;
+            let coords = match origin {
+                Some((region_x, region_z)) => (region_x * 32 + chunk_x as i32, region_z * 32 + chunk_z as i32),
+                None => (chunk.get_x_pos()?, chunk.get_z_pos()?),
+            };
+            return Some((coords, chunk));
+        }));
+    }
+
+    /// Returns an iterator over every chunk present in the Region except for those in the skip set, for resuming a
+    /// scan that was interrupted. Skipped chunks are not read at all. The coordinates in the set are the x and z of
+    /// the chunk within the Region, the same as the x and z fields of the yielded Chunks. Chunks are not cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `skip` - The coordinates of the chunks that have already been processed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// use std::collections::HashSet;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let mut done = HashSet::new();
+    /// for chunk in region.chunks_excluding(&HashSet::new()).take(10) {
+    ///     done.insert((chunk.x, chunk.z));
+    /// }
+    /// // Later on, pick up where the scan left off.
+    /// for chunk in region.chunks_excluding(&done) {
+    ///     println!("{}, {}", chunk.x, chunk.z);
+    /// }
+    /// ```
+    pub fn chunks_excluding<'b>(&'b self, skip: &'b HashSet<(u32, u32)>) -> Box<dyn Iterator<Item = Chunk> + 'b> {
+        return Box::new(
+            (0..1024u32)
+                .map(|index| (index % 32, index / 32))
+                .filter(move |coords| !skip.contains(coords))
+                .filter_map(move |(chunk_x, chunk_z)| Chunk::from_region(self, chunk_x, chunk_z)),
+        );
+    }
+
+    /// Returns the x and z coordinates of the region parsed from a filename like 'r.-1.2.mca'. None is returned if
+    /// the filename does not follow that pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.-3.5.mca".into()).unwrap();
+    /// assert_eq!(region.coords(), Some((-3, 5)));
+    /// ```
+    pub fn coords(&self) -> Option<(i32, i32)> {
+        return parse_region_name(&self.filename);
+    }
+
+    /// Returns the directory and position of the region, which are needed to find chunks stored in external .mcc
+    /// files. None if either is not known.
+    fn external_location(&self) -> Option<(&Path, (i32, i32))> {
+        return Some((self.directory.as_deref()?, self.coords()?));
+    }
+
+    /// Returns the biome of every column in a rectangular area at a particular height, as a grid indexed by x and
+    /// then z relative to the minimum corner, so the biome at x, z is at [x - min_x][z - min_z]. The corners are
+    /// world block coordinates and are both included in the area. Each chunk in the area is only read and decoded
+    /// once. Columns outside of this Region or in chunks that have not been generated are left as empty strings.
+    /// The position of the Region is parsed from its filename, a Region with a non-standard filename is treated as
+    /// r.0.0.mca. RegionError::AreaTooLarge is returned if the area is more than 512 blocks along either axis, the
+    /// size of a whole Region, since the rest of such an area could only ever be empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_xz` - The x and z coordinates of one corner of the area.
+    /// * `max_xz` - The x and z coordinates of the opposite corner of the area.
+    /// * `y` - The height to read the biomes at.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let biomes = region.biome_region((0, 0), (63, 63), 64).unwrap();
+    /// println!("{}", biomes[10][20]);
+    /// ```
+    pub fn biome_region(&self, min_xz: (i32, i32), max_xz: (i32, i32), y: i32) -> Result<Vec<Vec<String>>, RegionError> {
+        let (region_x, region_z) = self.coords().unwrap_or((0, 0));
+        let (min_x, max_x) = (cmp::min(min_xz.0, max_xz.0), cmp::max(min_xz.0, max_xz.0));
+        let (min_z, max_z) = (cmp::min(min_xz.1, max_xz.1), cmp::max(min_xz.1, max_xz.1));
+        let width = (max_x as i64 - min_x as i64 + 1) as u64;
+        let length = (max_z as i64 - min_z as i64 + 1) as u64;
+        if width > 512 || length > 512 {
+            return Err(RegionError::AreaTooLarge { width, length });
+        }
+        let mut grid = vec![vec![String::new(); length as usize]; width as usize];
+        let (min_chunk_x, min_chunk_z) = coords::block_to_chunk(min_x, min_z);
+        let (max_chunk_x, max_chunk_z) = coords::block_to_chunk(max_x, max_z);
+        for chunk_z in min_chunk_z..=max_chunk_z {
+            for chunk_x in min_chunk_x..=max_chunk_x {
+                let (local_x, local_z) = (chunk_x - region_x * 32, chunk_z - region_z * 32);
+                if !(0..32).contains(&local_x) || !(0..32).contains(&local_z) {
+                    continue;
+                }
+                let chunk = match Chunk::from_region(self, local_x as u32, local_z as u32) {
+                    Some(c) => c,
+                    None => continue,
+                };
+                let cells = match chunk.section_at(y).map(|section| section.biome_cells()) {
+                    Some(Ok(cells)) => cells,
+                    _ => continue,
+                };
+                if cells.is_empty() {
+                    continue;
+                }
+                for x in cmp::max(min_x, chunk_x * 16)..=cmp::min(max_x, chunk_x * 16 + 15) {
+                    for z in cmp::max(min_z, chunk_z * 16)..=cmp::min(max_z, chunk_z * 16 + 15) {
+                        let cell = (y.rem_euclid(16) / 4) * 16 + (z.rem_euclid(16) / 4) * 4 + x.rem_euclid(16) / 4;
+                        grid[(x - min_x) as usize][(z - min_z) as usize] = cells[cell as usize].to_string();
+                    }
+                }
+            }
+        }
+        return Ok(grid);
+    }
+
+    /// Returns the biome of every column in the Region at a particular height, for drawing biome maps. The map is
+    /// 512x512 with the biome of the column at x, z within the region at index x + z * 512. Each chunk is only read
+    /// once and biomes are looked up once per 4x4 cell, every column in a cell shares the same biome. Columns in
+    /// chunks that have not been generated far enough to have biomes are None.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The height to read the biomes at.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let map = region.biome_map(64);
+    /// if let Some(biome) = &map[10 + 20 * 512] {
+    ///     println!("{}", biome);
+    /// }
+    /// ```
+    pub fn biome_map(&self, y: i32) -> Vec<Option<String>> {
+        let mut map = vec![None; 512 * 512];
+        for chunk_z in 0..32u32 {
+            for chunk_x in 0..32u32 {
+                let chunk = match Chunk::from_region(self, chunk_x, chunk_z) {
+                    Some(c) => c,
+                    None => continue,
+                };
+                let cells = match chunk.section_at(y).map(|section| section.biome_cells()) {
+                    Some(Ok(cells)) => cells,
+                    _ => continue,
+                };
+                if cells.is_empty() {
+                    continue;
+                }
+                let row = (y.rem_euclid(16) / 4) as usize * 16;
+                for cell_z in 0..4 {
+                    for cell_x in 0..4 {
+                        let biome = cells[row + cell_z * 4 + cell_x].to_string();
+                        for z in 0..4 {
+                            for x in 0..4 {
+                                let map_x = chunk_x as usize * 16 + cell_x * 4 + x;
+                                let map_z = chunk_z as usize * 16 + cell_z * 4 + z;
+                                map[map_x + map_z * 512] = Some(biome.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return map;
+    }
+
+    /// Returns every entity stored across every chunk in the Region. Since 1.17 entities are kept in separate
+    /// region files in the world's entities folder, which use the same format as the block region files, so this
+    /// should be used on those files. Older region files keep entities with the blocks and work as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("entities/r.0.0.mca".into()).unwrap();
+    /// for entity in region.entities() {
+    ///     println!("{} at {:?}", entity.id, entity.pos);
+    /// }
+    /// ```
+    pub fn entities(&self) -> Vec<Entity> {
+        return self.chunks().flat_map(|chunk| chunk.get_entities()).collect();
+    }
+
+    /// Returns the number of each type of entity stored across every chunk in the Region. Since 1.17 entities are
+    /// kept in separate region files in the world's entities folder, so this should be used on those files. Chunks
+    /// without any entities are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let region = Region::from_file("entities/r.0.0.mca".into()).unwrap();
+    /// let counts = region.count_entities();
+    /// println!("{} zombies", counts.get("minecraft:zombie").unwrap_or(&0));
+    /// ```
+    pub fn count_entities(&self) -> HashMap<String, u64> {
+        let mut counts = HashMap::new();
+        for entity in self.chunks().flat_map(|chunk| chunk.get_entities()) {
+            *counts.entry(entity.id).or_insert(0) += 1;
+        }
+        return counts;
+    }
+
+    /// Returns the coordinates of every chunk whose blocks differ between this Region and another one. A chunk
+    /// that only exists in one of the two Regions is also reported. Only block content is compared, changes to
+    /// timestamps, lighting, entities, etc. are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The Region to compare against, typically an older copy of the same region file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    ///
+    /// let old = Region::from_file("backup/r.0.0.mca".into()).unwrap();
+    /// let new = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// for (x, z) in new.diff(&old) {
+    ///     println!("Chunk {}, {} changed", x, z);
+    /// }
+    /// ```
+    pub fn diff(&self, other: &Region) -> Vec<(u32, u32)> {
+        let mut changed = Vec::new();
+        for chunk_z in 0..32 {
+            for chunk_x in 0..32 {
+                let same = match (Chunk::from_region(self, chunk_x, chunk_z), Chunk::from_region(other, chunk_x, chunk_z)) {
+                    (None, None) => true,
+                    (Some(a), Some(b)) => a.blocks_equal(&b),
+                    _ => false,
+                };
+                if !same {
+                    changed.push((chunk_x, chunk_z));
+                }
+            }
+        }
+        return changed;
+    }
+}
+
+/// A region file that is read from disk as chunks are requested, created with Region::open_lazy. Only the location
+/// and timestamp tables are kept in memory.
+#[derive(Debug)]
+pub struct LazyRegion {
+    /// The open region file, behind a lock so that a seek and the read that follows it can't be interleaved.
+    file: Mutex<File>,
+    /// The first two sectors of the file, containing the location and timestamp tables.
+    header: Vec<u8>,
+    /// The name of the file that the region was derived from.
+    pub filename: String,
+    /// The directory containing the region file, used to find chunks stored in external .mcc files.
+    directory: Option<PathBuf>,
+}
+
+impl LazyRegion {
+    /// Returns a Chunk contained within the region, read from the file. None is returned if the chunk has not been
+    /// generated or could not be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_x` - The x coordinate of the particular chunk
+    /// * `chunk_z` - The z coordinate of the particular chunk
+    pub fn get_chunk(&self, chunk_x: u32, chunk_z: u32) -> Option<Chunk> {
+        let (data, little_endian) = self.read_chunk(chunk_x, chunk_z).ok().flatten()?;
+        return Some(Chunk::from_data(data, chunk_x, chunk_z, little_endian));
+    }
+
+    /// Returns the location and timestamp tables from the start of the region file.
+    pub fn header(&self) -> RegionHeader {
+        return RegionHeader::from_bytes(&self.header);
+    }
+
+    /// Returns a Blob of all the data for a particular chunk, or None if the chunk has not been generated.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_x` - The x coordinate of the particular chunk
+    /// * `chunk_z` - The z coordinate of the particular chunk
+    pub fn try_chunk_data(&self, chunk_x: u32, chunk_z: u32) -> Result<Option<Box<Blob>>, RegionError> {
+        return Ok(self.read_chunk(chunk_x, chunk_z)?.map(|(data, _)| data));
+    }
+
+    /// Reads a chunk from the file, returning the Blob and whether it was stored as little-endian NBT.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_x` - The x coordinate of the particular chunk
+    /// * `chunk_z` - The z coordinate of the particular chunk
+    fn read_chunk(&self, chunk_x: u32, chunk_z: u32) -> Result<Option<(Box<Blob>, bool)>, RegionError> {
+        let location = location_entry(&self.header, header_offset(chunk_x, chunk_z) as usize);
+        if location == (0, 0) {
+            return Ok(None);
+        }
+        if location.0 < 2 {
+            return Err(RegionError::HeaderOverlap { chunk_x, chunk_z, sector: location.0 });
+        }
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let file_length = file.metadata().map_err(RegionError::Io)?.len();
+        file.seek(SeekFrom::Start(location.0 as u64 * 4096)).map_err(RegionError::Io)?;
+        let mut chunk_header = [0; 5];
+        file.read_exact(&mut chunk_header).map_err(RegionError::Io)?;
+        let length = u32::from_be_bytes([chunk_header[0], chunk_header[1], chunk_header[2], chunk_header[3]]);
+        if length == 0 {
+            return Err(RegionError::ZeroLength { chunk_x, chunk_z });
+        }
+        if chunk_header[4] & EXTERNAL_FLAG != 0 {
+            let external = self.directory.as_deref().zip(parse_region_name(&self.filename));
+            return read_external_chunk(external, chunk_x, chunk_z, chunk_header[4]);
+        }
+        // The length is checked before allocating anything, as a corrupt header could otherwise ask for up to 4 GiB.
+        // It has to fit in the sectors given to the chunk, and the chunk can end on the last byte of the file.
+        let end = location.0 as u64 * 4096 + 4 + length as u64;
+        if 4 + length as u64 > location.1 as u64 * 4096 || end > file_length {
+            return Err(RegionError::Truncated { chunk_x, chunk_z });
+        }
+        let mut compressed_data = vec![0; length as usize - 1];
+        file.read_exact(&mut compressed_data).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => RegionError::Truncated { chunk_x, chunk_z },
+            _ => RegionError::Io(e),
+        })?;
+        return decode_chunk(&compressed_data, chunk_header[4]);
+    }
+}
+
+/// Builds small region files in memory, mainly for use as test fixtures. Chunks can either be filled in block by
+/// block, in which case they are written in the 1.18+ format with every other block set to air, or be given their
+/// complete NBT. Only the parts of a chunk that this crate reads are written, so the output is not intended to be
+/// loaded by Minecraft.
+///
+/// # Examples
+///
+/// ```rust
+/// use simple_anvil::{block::Block, region::RegionBuilder};
+///
+/// let stone = Block::from_name("minecraft:stone".into(), None, None, None);
+/// let region = RegionBuilder::new("r.0.0.mca".into())
+///     .set_block(0, 0, 1, 64, 2, &stone)
+///     .build();
+/// assert!(region.get_chunk(0, 0).is_some());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RegionBuilder {
+    /// The name of the file that the region will claim to come from.
+    filename: String,
+    /// The blocks that have been set in each chunk, keyed by the local coordinates of the block.
+    blocks: HashMap<(u32, u32), HashMap<(i32, i32, i32), Block>>,
+    /// Chunks whose complete NBT has been provided.
+    raw: HashMap<(u32, u32), Blob>,
+}
+
+impl RegionBuilder {
+    /// The DataVersion written into generated chunks, matching Minecraft 1.18.2.
+    const DATA_VERSION: i32 = 2975;
+
+    /// Returns an empty builder. The position of the region is parsed from the filename and used for the xPos and
+    /// zPos of generated chunks, a non-standard filename places the region at r.0.0.mca.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The filename for the built Region, ie. 'r.0.0.mca'
+    pub fn new(filename: String) -> RegionBuilder {
+        return RegionBuilder { filename, ..Default::default() };
+    }
+
+    /// Sets a block in a chunk, creating the chunk if it does not exist yet. The coordinates and biome of the
+    /// block itself are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_x` - The x coordinate of the chunk within the region (0-31)
+    /// * `chunk_z` - The z coordinate of the chunk within the region (0-31)
+    /// * `x` - The x coordinate of the block within the chunk (0-15)
+    /// * `y` - The y coordinate of the block (-64-319)
+    /// * `z` - The z coordinate of the block within the chunk (0-15)
+    /// * `block` - The block to place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the coordinates are outside of the ranges above.
+    pub fn set_block(&mut self, chunk_x: u32, chunk_z: u32, x: i32, y: i32, z: i32, block: &Block) -> &mut RegionBuilder {
+        assert!(chunk_x < 32 && chunk_z < 32, "Chunk {}, {} is outside of the region", chunk_x, chunk_z);
+        assert!(
+            (0..16).contains(&x) && (-64..320).contains(&y) && (0..16).contains(&z),
+            "Block {}, {}, {} is outside of the chunk",
+            x, y, z
+        );
+        self.blocks.entry((chunk_x, chunk_z)).or_default().insert((x, y, z), block.clone());
+        return self;
+    }
+
+    /// Adds an empty chunk, one that has been generated but only contains air.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_x` - The x coordinate of the chunk within the region (0-31)
+    /// * `chunk_z` - The z coordinate of the chunk within the region (0-31)
+    ///
+    /// # Panics
+    ///
+    /// Panics if either of the coordinates is outside of the range above.
+    pub fn empty_chunk(&mut self, chunk_x: u32, chunk_z: u32) -> &mut RegionBuilder {
+        assert!(chunk_x < 32 && chunk_z < 32, "Chunk {}, {} is outside of the region", chunk_x, chunk_z);
+        self.blocks.entry((chunk_x, chunk_z)).or_default();
+        return self;
+    }
+
+    /// Adds a chunk with its complete NBT, replacing any blocks set in the chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_x` - The x coordinate of the chunk within the region (0-31)
+    /// * `chunk_z` - The z coordinate of the chunk within the region (0-31)
+    /// * `data` - The NBT for the chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either of the coordinates is outside of the range above.
+    pub fn chunk_nbt(&mut self, chunk_x: u32, chunk_z: u32, data: Blob) -> &mut RegionBuilder {
+        assert!(chunk_x < 32 && chunk_z < 32, "Chunk {}, {} is outside of the region", chunk_x, chunk_z);
+        self.raw.insert((chunk_x, chunk_z), data);
+        return self;
+    }
+
+    /// Returns the contents of a region file containing every chunk that has been added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a single chunk is larger than 1 MiB once compressed.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (region_x, region_z) = parse_region_name(&self.filename).unwrap_or((0, 0));
+        let mut chunks = self.raw.clone();
+        for (coords, blocks) in &self.blocks {
+            if !chunks.contains_key(coords) {
+                let position = (region_x * 32 + coords.0 as i32, region_z * 32 + coords.1 as i32);
+                chunks.insert(*coords, chunk_blob(position, blocks));
+            }
+        }
+        let mut coords = chunks.keys().copied().collect::<Vec<(u32, u32)>>();
+        coords.sort_by_key(|(x, z)| (*z, *x));
+
+        let mut data = vec![0u8; 8192];
+        for (chunk_x, chunk_z) in coords {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            chunks[&(chunk_x, chunk_z)].to_writer(&mut encoder).expect("Failed to write chunk NBT");
+            let compressed = encoder.finish().expect("Failed to compress chunk");
+
+            let sector = (data.len() / 4096) as u32;
+            let sectors = (compressed.len() + 5).div_ceil(4096);
+            assert!(sectors <= 255, "Chunk {}, {} is too large", chunk_x, chunk_z);
+            let entry = (4 * (chunk_x + chunk_z * 32)) as usize;
+            data[entry..entry + 3].copy_from_slice(&sector.to_be_bytes()[1..]);
+            data[entry + 3] = sectors as u8;
+
+            data.extend_from_slice(&(compressed.len() as u32 + 1).to_be_bytes());
+            data.push(2);
+            data.extend_from_slice(&compressed);
+            data.resize((sector as usize + sectors) * 4096, 0);
+        }
+        return data;
+    }
+
+    /// Returns a Region containing every chunk that has been added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a single chunk is larger than 1 MiB once compressed.
+    pub fn build(&self) -> Region {
+        return Region::from_bytes(self.to_bytes(), self.filename.clone());
+    }
+}
+
+/// Returns the NBT for a chunk in the 1.18+ format containing the given blocks, with everything else set to air.
+///
+/// # Arguments
+///
+/// * `coords` - The absolute coordinates of the chunk.
+/// * `blocks` - The blocks in the chunk, keyed by their local coordinates.
+fn chunk_blob(coords: (i32, i32), blocks: &HashMap<(i32, i32, i32), Block>) -> Blob {
+    let mut sections = Vec::new();
+    for section_y in -4..20 {
+        let mut palette = vec![palette_entry(&Block::from_name("minecraft:air".into(), None, None, None))];
+        let mut indices = vec![0usize; 4096];
+        for ((x, y, z), block) in blocks.iter().filter(|(c, _)| c.1.div_euclid(16) == section_y) {
+            let entry = palette_entry(block);
+            let index = match palette.iter().position(|p| *p == entry) {
+                Some(i) => i,
+                None => {
+                    palette.push(entry);
+                    palette.len() - 1
+                }
+            };
+            indices[(y.rem_euclid(16) * 256 + z * 16 + x) as usize] = index;
+        }
+
+        let mut block_states = HashMap::new();
+        if palette.len() > 1 {
+            let bits = cmp::max(chunk::bit_length(palette.len() - 1), 4) as usize;
+            let per_long = 64 / bits;
+            let mut data = vec![0i64; 4096usize.div_ceil(per_long)];
+            for (n, index) in indices.iter().enumerate() {
+                data[n / per_long] |= ((*index as u64) << (n % per_long * bits)) as i64;
+            }
+            block_states.insert("data".to_string(), Value::LongArray(data));
+        }
+        block_states.insert("palette".to_string(), Value::List(palette));
+
+        let mut biomes = HashMap::new();
+        biomes.insert("palette".to_string(), Value::List(vec![Value::String("minecraft:plains".into())]));
+
+        let mut section = HashMap::new();
+        section.insert("Y".to_string(), Value::Byte(section_y as i8));
+        section.insert("block_states".to_string(), Value::Compound(block_states));
+        section.insert("biomes".to_string(), Value::Compound(biomes));
+        sections.push(Value::Compound(section));
+    }
+
+    let mut world_surface = [-65; 256];
+    let mut ocean_floor = [-65; 256];
+    for ((x, y, z), block) in blocks {
+        let name = block.to_string();
+        let column = (z * 16 + x) as usize;
+        if name != "minecraft:air" && name != "minecraft:cave_air" && name != "minecraft:void_air" {
+            world_surface[column] = cmp::max(world_surface[column], *y);
+            if name != "minecraft:water" && name != "minecraft:lava" {
+                ocean_floor[column] = cmp::max(ocean_floor[column], *y);
+            }
+        }
+    }
+    let mut heightmaps = HashMap::new();
+    heightmaps.insert("WORLD_SURFACE".to_string(), Value::LongArray(pack_heightmap(&world_surface)));
+    heightmaps.insert("OCEAN_FLOOR".to_string(), Value::LongArray(pack_heightmap(&ocean_floor)));
+
+    let mut blob = Blob::new();
+    let fields = vec![
+        ("DataVersion", Value::Int(RegionBuilder::DATA_VERSION)),
+        ("xPos", Value::Int(coords.0)),
+        ("zPos", Value::Int(coords.1)),
+        ("yPos", Value::Int(-4)),
+        ("Status", Value::String("full".into())),
+        ("LastUpdate", Value::Long(0)),
+        ("InhabitedTime", Value::Long(0)),
+        ("sections", Value::List(sections)),
+        ("Heightmaps", Value::Compound(heightmaps)),
+    ];
+    for (name, value) in fields {
+        blob.insert(name, value).expect("Generated chunk NBT should be valid");
+    }
+    return blob;
+}
+
+/// Returns the palette compound for a block.
+///
+/// # Arguments
+///
+/// * `block` - The block to describe.
+fn palette_entry(block: &Block) -> Value {
+    let mut entry = HashMap::new();
+    entry.insert("Name".to_string(), Value::String(block.to_string()));
+    if let Some(properties) = &block.properties {
+        let properties = properties.iter().map(|(k, v)| (k.to_owned(), Value::String(v.to_owned()))).collect();
+        entry.insert("Properties".to_string(), Value::Compound(properties));
+    }
+    return Value::Compound(entry);
+}
+
+/// Returns a packed heightmap from the y of the highest block in each column, ordered by z and then x.
+///
+/// # Arguments
+///
+/// * `heights` - The y of the highest block in each column, -65 for an empty column.
+fn pack_heightmap(heights: &[i32; 256]) -> Vec<i64> {
+    let mut packed = vec![0i64; 37];
+    for (n, height) in heights.iter().enumerate() {
+        packed[n / 7] |= (((height + 65) as u64) << (n % 7 * 9)) as i64;
+    }
+    return packed;
+}
+
+/// Returns the sector offset and sector count from an entry in the location table. An entry past the end of the
+/// data is read as (0, 0), the same as a chunk that is not present.
+///
+/// # Arguments
+///
+/// * `header` - The region data, starting with the location table.
+/// * `offset` - The offset of the entry, as returned by header_offset.
+fn location_entry(header: &[u8], offset: usize) -> (u32, u32) {
+    return match header.get(offset..offset + 4) {
+        Some(entry) => (from_be_3_bytes([entry[0], entry[1], entry[2]]), entry[3] as u32),
+        None => (0, 0),
+    };
+}
+
+/// Returns the header size and returns an offset for a particular chunk.
+/// 
+/// # Arguments
+/// 
+/// * `chunk_x` - The x coordinate of the particular chunk
+/// * `chunk_z` - The z coordinate of the particular chunk
+fn header_offset(chunk_x: u32, chunk_z: u32) -> u32 {
+    return 4 * (chunk_x % 32 + chunk_z % 32 * 32);
+}
+
+/// Returns the Blob for a chunk read from the data of a whole region file, along with whether the NBT had to be
+/// read as little-endian. This is kept apart from Region so that it can be used without borrowing the Region.
+///
+/// # Arguments
+///
+/// * `data` - The region data, starting with the location table.
+/// * `external` - The directory and position of the region, used to find chunks stored in .mcc files.
+/// * `chunk_x` - The x coordinate of the particular chunk
+/// * `chunk_z` - The z coordinate of the particular chunk
+fn read_chunk_from(
+    data: &[u8],
+    external: Option<(&Path, (i32, i32))>,
+    chunk_x: u32,
+    chunk_z: u32,
+) -> Result<Option<(Box<Blob>, bool)>, RegionError> {
+    let off = location_entry(data, header_offset(chunk_x, chunk_z) as usize);
+    if off == (0, 0) {
+        return Ok(None);
+    }
+    if off.0 < 2 {
+        return Err(RegionError::HeaderOverlap { chunk_x, chunk_z, sector: off.0 });
+    }
+    let sectors = off.1;
+    let off = off.0 as usize * 4096;
+
+    let chunk_header = match data.get(off..off + 5) {
+        Some(h) => h,
+        None => return Err(RegionError::Truncated { chunk_x, chunk_z }),
+    };
+    let length = u32::from_be_bytes([chunk_header[0], chunk_header[1], chunk_header[2], chunk_header[3]]);
+    if length == 0 {
+        return Err(RegionError::ZeroLength { chunk_x, chunk_z });
+    }
+    let compression = chunk_header[4];
+    if compression & EXTERNAL_FLAG != 0 {
+        return read_external_chunk(external, chunk_x, chunk_z, compression);
+    }
+    // The stored length counts the compression type byte, so the payload is one byte shorter. It has to fit in
+    // the sectors given to the chunk, and a chunk at the end of the file can finish exactly on the last byte.
+    if 4 + length as u64 > sectors as u64 * 4096 {
+        return Err(RegionError::Truncated { chunk_x, chunk_z });
+    }
+    let compressed_data = match data.get(off + 5..(off + 4).saturating_add(length as usize)) {
+        Some(d) => d,
+        None => return Err(RegionError::Truncated { chunk_x, chunk_z }),
+    };
+    return decode_chunk(compressed_data, compression);
+}
+
+/// Set on the compression type of a chunk that is too large for the region file, the chunk is then stored on its own
+/// in a file named c.X.Z.mcc next to the region, where X and Z are the absolute chunk coordinates.
+const EXTERNAL_FLAG: u8 = 0x80;
+
+/// Returns the Blob for a chunk stored in an external .mcc file. The file holds only the compressed payload, the
+/// compression type is the one stored in the region with the external flag removed.
+///
+/// # Arguments
+///
+/// * `external` - The directory and position of the region, None if they are not known.
+/// * `chunk_x` - The x coordinate of the particular chunk
+/// * `chunk_z` - The z coordinate of the particular chunk
+/// * `compression` - The compression type stored in the region, including the external flag.
+fn read_external_chunk(
+    external: Option<(&Path, (i32, i32))>,
+    chunk_x: u32,
+    chunk_z: u32,
+    compression: u8,
+) -> Result<Option<(Box<Blob>, bool)>, RegionError> {
+    let (directory, (region_x, region_z)) = match external {
+        Some(e) => e,
+        None => return Err(RegionError::ExternalChunk { chunk_x, chunk_z }),
+    };
+    let name = format!("c.{}.{}.mcc", region_x * 32 + chunk_x as i32, region_z * 32 + chunk_z as i32);
+    let data = fs::read(directory.join(name)).map_err(RegionError::Io)?;
+    return decode_chunk(&data, compression & !EXTERNAL_FLAG);
+}
+
+/// Returns the x and z coordinates of a region from a filename like 'r.-1.2.mca', None if the filename does not
+/// follow that pattern.
+///
+/// # Arguments
+///
+/// * `filename` - The name of the region file.
+fn parse_region_name(filename: &str) -> Option<(i32, i32)> {
+    let mut parts = filename.split('.');
+    if parts.next() != Some("r") {
+        return None;
+    }
+    let x = parts.next()?.parse::<i32>().ok()?;
+    let z = parts.next()?.parse::<i32>().ok()?;
+    if parts.next() != Some("mca") || parts.next().is_some() {
+        return None;
+    }
+    return Some((x, z));
+}
+
+/// Returns the Blob for a chunk from its compressed payload, along with whether the NBT had to be read as
+/// little-endian. Region files should always contain big-endian NBT, but some third-party tools write
+/// little-endian NBT by mistake so that is tried when the big-endian read fails.
+///
+/// # Arguments
+///
+/// * `compressed_data` - The chunk payload following the length and compression type.
+/// * `compression` - The compression type of the payload.
+fn decode_chunk(compressed_data: &[u8], compression: u8) -> Result<Option<(Box<Blob>, bool)>, RegionError> {
+    let decompressed = match compression {
+        1 => read_decoder(GzDecoder::new(compressed_data))?,
+        2 => read_decoder(ZlibDecoder::new(compressed_data))?,
+        3 => compressed_data.to_vec(),
+        #[cfg(feature = "lz4")]
+        4 => decompress_lz4(compressed_data)?,
+        _ => return Err(RegionError::UnsupportedCompression(compression)),
+    };
+    return match Blob::from_reader(&mut decompressed.as_slice()) {
+        Ok(data) => Ok(Some((Box::new(data), false))),
+        Err(e) => match little_endian::read_blob(&decompressed) {
+            Some(data) => Ok(Some((Box::new(data), true))),
+            None => Err(RegionError::Nbt(e)),
+        },
+    };
+}
+
+/// Returns everything read from a gzip or zlib decoder.
+///
+/// # Arguments
+///
+/// * `decoder` - The decoder wrapping the chunk payload.
+fn read_decoder<R: Read>(mut decoder: R) -> Result<Vec<u8>, RegionError> {
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed).map_err(|e| RegionError::Nbt(nbt::Error::from(e)))?;
+    return Ok(decompressed);
+}
+
+/// Returns the decompressed contents of an LZ4 chunk payload. Minecraft writes these with the block stream format
+/// from lz4-java, a series of blocks that each start with the magic 'LZ4Block', a token giving the method, and
+/// the compressed and decompressed lengths and a checksum as little-endian ints. The stream ends with an empty
+/// block, which some writers leave off. Checksums are not verified.
+///
+/// # Arguments
+///
+/// * `data` - The chunk payload following the length and compression type.
+#[cfg(feature = "lz4")]
+fn decompress_lz4(mut data: &[u8]) -> Result<Vec<u8>, RegionError> {
+    let invalid = |message: &str| RegionError::Nbt(nbt::Error::from(io::Error::new(io::ErrorKind::InvalidData, message)));
+    let mut decompressed = Vec::new();
+    loop {
+        if data.is_empty() {
+            return Ok(decompressed);
+        }
+        if data.len() < 21 || &data[..8] != b"LZ4Block" {
+            return Err(invalid("invalid LZ4 block header"));
+        }
+        let method = data[8] & 0xF0;
+        let compressed_len = u32::from_le_bytes([data[9], data[10], data[11], data[12]]) as usize;
+        let decompressed_len = u32::from_le_bytes([data[13], data[14], data[15], data[16]]) as usize;
+        data = &data[21..];
+        if decompressed_len == 0 {
+            return Ok(decompressed);
+        }
+        if data.len() < compressed_len {
+            return Err(invalid("LZ4 block runs past the end of the chunk"));
+        }
+        match method {
+            0x10 => decompressed.extend_from_slice(&data[..compressed_len]),
+            0x20 => decompressed.extend(
+                lz4_flex::block::decompress(&data[..compressed_len], decompressed_len)
+                    .map_err(|_| invalid("invalid LZ4 block data"))?,
+            ),
+            _ => return Err(invalid("unknown LZ4 block method")),
+        }
+        data = &data[compressed_len..];
+    }
+}
+
+/// Returns an unsigned int from three bytes. This might not be needed anymore.
+/// 
+/// # Arguments
+/// 
+/// * `bytes` - The bytes to be converted into u32
+fn from_be_3_bytes(bytes: [u8; 3]) -> u32 {
+    let mut temp: [u8; 4] = [0; 4];
+    for n in 0..bytes.len() {
+        temp[n + 1] = bytes[n];
+    }
+    return u32::from_be_bytes(temp);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    fn stone() -> Block {
+        return Block::from_name("minecraft:stone".into(), None, None, None);
+    }
+
+    #[test]
+    fn builder_round_trips_blocks() {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(2, 3, 4, 64, 5, &stone()).build();
+        let chunk = region.get_chunk(2, 3).unwrap();
+        assert_eq!(chunk.get_block(4, 64, 5).unwrap().name(), "minecraft:stone");
+        assert_eq!(chunk.get_block(4, 65, 5).unwrap().name(), "minecraft:air");
+        assert!(region.get_chunk(0, 0).is_none());
+    }
+
+    #[test]
+    fn builder_writes_absolute_chunk_positions() {
+        let region = RegionBuilder::new("r.-1.2.mca".into()).set_block(3, 4, 0, 0, 0, &stone()).build();
+        let chunk = region.get_chunk(3, 4).unwrap();
+        assert_eq!(chunk.get_x_pos(), Some(-29));
+        assert_eq!(chunk.get_z_pos(), Some(68));
+        assert!(chunk.position_matches_region(&region));
+        assert_eq!(chunk.get_block(0, 0, 0).unwrap().coords, Some((-464, 0, 1088)));
+    }
+
+    /// Returns the bytes of a region holding a single chunk at 0, 0, cut off right after the chunk's payload.
+    fn region_ending_at_chunk() -> Vec<u8> {
+        let mut data = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 64, 0, &stone()).to_bytes();
+        let length = u32::from_be_bytes([data[8192], data[8193], data[8194], data[8195]]) as usize;
+        data.truncate(8192 + 4 + length);
+        return data;
+    }
+
+    #[test]
+    fn reads_chunk_ending_exactly_at_end_of_file() {
+        let region = Region::from_bytes(region_ending_at_chunk(), "r.0.0.mca".into());
+        assert!(region.try_chunk_data(0, 0).unwrap().is_some());
+    }
+
+    #[test]
+    fn rejects_chunk_one_byte_past_end_of_file() {
+        let mut data = region_ending_at_chunk();
+        data.pop();
+        let region = Region::from_bytes(data, "r.0.0.mca".into());
+        assert!(matches!(region.try_chunk_data(0, 0), Err(RegionError::Truncated { chunk_x: 0, chunk_z: 0 })));
+    }
+
+    #[test]
+    fn rejects_chunk_longer_than_its_sectors() {
+        let mut data = region_ending_at_chunk();
+        data.resize(8192 + 3 * 4096, 0);
+        data[8192..8196].copy_from_slice(&4096u32.to_be_bytes());
+        let region = Region::from_bytes(data, "r.0.0.mca".into());
+        assert!(matches!(region.try_chunk_data(0, 0), Err(RegionError::Truncated { chunk_x: 0, chunk_z: 0 })));
+    }
+
+    /// Writes the bytes of a region to a temporary file named after the test and opens it lazily.
+    fn lazy_region(name: &str, data: &[u8]) -> LazyRegion {
+        let directory = std::env::temp_dir().join(format!("simple-anvil-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("r.0.0.mca");
+        std::fs::write(&path, data).unwrap();
+        let region = Region::open_lazy(&path).unwrap();
+        // The region keeps the file open, so on unix it can still be read after the directory is removed.
+        let _ = std::fs::remove_dir_all(&directory);
+        return region;
+    }
+
+    /// Overwrites the length stored at the start of the chunk at 0, 0 in region_ending_at_chunk.
+    fn set_length(data: &mut [u8], length: u32) {
+        data[8192..8196].copy_from_slice(&length.to_be_bytes());
+    }
+
+    #[test]
+    fn lazy_reads_chunk_ending_exactly_at_end_of_file() {
+        let region = lazy_region("lazy-exact", &region_ending_at_chunk());
+        assert!(region.try_chunk_data(0, 0).unwrap().is_some());
+    }
+
+    #[test]
+    fn lazy_rejects_bad_chunk_lengths() {
+        let mut data = region_ending_at_chunk();
+        data.pop();
+        let region = lazy_region("lazy-short", &data);
+        assert!(matches!(region.try_chunk_data(0, 0), Err(RegionError::Truncated { chunk_x: 0, chunk_z: 0 })));
+
+        let mut data = region_ending_at_chunk();
+        set_length(&mut data, 0);
+        let region = lazy_region("lazy-zero", &data);
+        assert!(matches!(region.try_chunk_data(0, 0), Err(RegionError::ZeroLength { chunk_x: 0, chunk_z: 0 })));
+
+        let mut data = region_ending_at_chunk();
+        data.resize(8192 + 3 * 4096, 0);
+        set_length(&mut data, 4096);
+        let region = lazy_region("lazy-sectors", &data);
+        assert!(matches!(region.try_chunk_data(0, 0), Err(RegionError::Truncated { chunk_x: 0, chunk_z: 0 })));
+
+        let mut data = region_ending_at_chunk();
+        set_length(&mut data, u32::MAX);
+        let region = lazy_region("lazy-huge", &data);
+        assert!(matches!(region.try_chunk_data(0, 0), Err(RegionError::Truncated { chunk_x: 0, chunk_z: 0 })));
+    }
+
+    #[test]
+    fn chunks_with_coords_falls_back_to_position_tags() {
+        let blob = RegionBuilder::new("r.-1.2.mca".into()).set_block(3, 4, 0, 0, 0, &stone()).build().get_chunk(3, 4).unwrap().data;
+        let region = RegionBuilder::new("r.-1.2.mca".into()).chunk_nbt(3, 4, *blob.clone()).build();
+        assert_eq!(region.chunks_with_coords().map(|(coords, _)| coords).collect::<Vec<_>>(), vec![(-29, 68)]);
+        let region = RegionBuilder::new("region.mca".into()).chunk_nbt(3, 4, *blob).build();
+        assert_eq!(region.chunks_with_coords().map(|(coords, _)| coords).collect::<Vec<_>>(), vec![(-29, 68)]);
+    }
+
+    /// Returns the bytes of a region holding the chunk at 0, 0 from region_ending_at_chunk, with its NBT
+    /// re-encoded as the given payload and compression type.
+    fn region_with_payload(payload: &[u8], compression: u8) -> Vec<u8> {
+        let mut data = region_ending_at_chunk();
+        data.truncate(8192);
+        data.extend_from_slice(&(payload.len() as u32 + 1).to_be_bytes());
+        data.push(compression);
+        data.extend_from_slice(payload);
+        data[3] = (payload.len() + 5).div_ceil(4096) as u8;
+        return data;
+    }
+
+    /// Returns the uncompressed NBT of the chunk in region_ending_at_chunk.
+    fn chunk_nbt() -> Vec<u8> {
+        let data = region_ending_at_chunk();
+        return read_decoder(ZlibDecoder::new(&data[8197..])).unwrap();
+    }
+
+    #[test]
+    fn reads_every_standard_compression_type() {
+        let nbt = chunk_nbt();
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&nbt).unwrap();
+        let gzip = gzip.finish().unwrap();
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(&nbt).unwrap();
+        let zlib = zlib.finish().unwrap();
+        for (payload, compression) in [(gzip, 1), (zlib, 2), (nbt, 3)] {
+            let region = Region::from_bytes(region_with_payload(&payload, compression), "r.0.0.mca".into());
+            let chunk = region.get_chunk(0, 0).unwrap();
+            assert_eq!(chunk.get_block(0, 64, 0).unwrap().name(), "minecraft:stone");
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_compression_types() {
+        let nbt = chunk_nbt();
+        for compression in [0, 5, 127] {
+            let region = Region::from_bytes(region_with_payload(&nbt, compression), "r.0.0.mca".into());
+            assert!(matches!(region.try_chunk_data(0, 0), Err(RegionError::UnsupportedCompression(c)) if c == compression));
+            assert!(region.chunk_data(0, 0).is_none());
+        }
+    }
+
+    /// Returns an entity chunk, as stored in the entities folder, holding an entity with each of the given ids.
+    fn entity_chunk(ids: &[&str]) -> Blob {
+        let entities = ids
+            .iter()
+            .map(|id| {
+                let mut entity = HashMap::new();
+                entity.insert("id".to_string(), Value::String(id.to_string()));
+                entity.insert("Pos".to_string(), Value::List(vec![Value::Double(0.5), Value::Double(64.0), Value::Double(0.5)]));
+                Value::Compound(entity)
+            })
+            .collect();
+        let mut blob = Blob::new();
+        blob.insert("DataVersion", Value::Int(RegionBuilder::DATA_VERSION)).unwrap();
+        blob.insert("Entities", Value::List(entities)).unwrap();
+        return blob;
+    }
+
+    #[test]
+    fn count_entities_adds_up_every_chunk() {
+        let region = RegionBuilder::new("r.0.0.mca".into())
+            .chunk_nbt(0, 0, entity_chunk(&["minecraft:zombie", "minecraft:cow"]))
+            .chunk_nbt(31, 31, entity_chunk(&["minecraft:zombie"]))
+            .chunk_nbt(5, 7, entity_chunk(&[]))
+            .build();
+        let counts = region.count_entities();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["minecraft:zombie"], 2);
+        assert_eq!(counts["minecraft:cow"], 1);
+        assert_eq!(region.entities().len(), 3);
+    }
+
+    #[test]
+    fn biome_region_rejects_areas_larger_than_the_region() {
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 64, 0, &stone()).build();
+        let biomes = region.biome_region((0, 0), (20, 3), 64).unwrap();
+        assert_eq!((biomes.len(), biomes[0].len()), (21, 4));
+        assert_eq!(biomes[0][0], "minecraft:plains");
+        assert_eq!(biomes[20][0], "");
+        let result = region.biome_region((i32::MIN, i32::MIN), (i32::MAX, i32::MAX), 64);
+        assert!(matches!(result, Err(RegionError::AreaTooLarge { width: 4294967296, length: 4294967296 })));
+        let result = region.biome_region((0, 0), (999_999, 999_999), 64);
+        assert!(matches!(result, Err(RegionError::AreaTooLarge { width: 1_000_000, length: 1_000_000 })));
+        let result = region.biome_region((-100, 0), (412, 0), 64);
+        assert!(matches!(result, Err(RegionError::AreaTooLarge { width: 513, length: 1 })));
+        assert_eq!(region.biome_region((-100, 0), (411, 511), 64).unwrap().len(), 512);
+    }
+
+    #[test]
+    fn scans_do_not_fill_the_cache() {
+        let region = RegionBuilder::new("r.0.0.mca".into())
+            .set_block(0, 0, 0, 64, 0, &stone())
+            .set_block(1, 0, 0, 64, 0, &stone())
+            .build();
+        assert_eq!(region.chunks().count(), 2);
+        assert_eq!(region.chunks_with_coords().count(), 2);
+        assert!(region.diff(&region.clone()).is_empty());
+        region.biome_map(64);
+        region.count_entities();
+        assert!(region.cache.lock().unwrap().is_empty());
+        region.get_chunk(1, 0).unwrap();
+        assert_eq!(region.cache.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_rejects_blocks_outside_of_the_chunk() {
+        RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 16, 64, 0, &stone());
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_rejects_blocks_outside_of_the_world() {
+        RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 320, 0, &stone());
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_rejects_chunks_outside_of_the_region() {
+        RegionBuilder::new("r.0.0.mca".into()).set_block(32, 0, 0, 64, 0, &stone());
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_rejects_empty_chunks_outside_of_the_region() {
+        RegionBuilder::new("r.0.0.mca".into()).empty_chunk(0, 32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_rejects_chunk_nbt_outside_of_the_region() {
+        RegionBuilder::new("r.0.0.mca".into()).chunk_nbt(32, 0, Blob::new());
     }
-    return u32::from_be_bytes(temp);
 }
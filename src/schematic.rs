@@ -0,0 +1,100 @@
+use nbt::{Blob, Value};
+
+use std::{collections::HashMap, io};
+
+/// A box of blocks copied out of a world, stored the same way as a Sponge schematic (version 2). Each block is an
+/// index into the palette, and the blocks are ordered by y, then z, then x, so the block at x, y, z relative to the
+/// minimum corner is at index x + z * width + y * width * length.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schematic {
+    /// The size of the schematic along the x axis.
+    pub width: u16,
+    /// The size of the schematic along the y axis.
+    pub height: u16,
+    /// The size of the schematic along the z axis.
+    pub length: u16,
+    /// The world coordinates of the minimum corner that the schematic was taken from.
+    pub offset: (i32, i32, i32),
+    /// The DataVersion of the chunks that the blocks came from.
+    pub data_version: i32,
+    /// Each distinct block state, ie. 'minecraft:oak_stairs[facing=east,half=bottom]'
+    pub palette: Vec<String>,
+    /// The palette index of every block.
+    pub blocks: Vec<u32>,
+    /// The block entities within the box, in the Sponge layout with a Pos relative to the minimum corner and an Id.
+    pub block_entities: Vec<HashMap<String, Value>>,
+}
+
+impl Schematic {
+    /// Returns the block state of the block at x, y, z relative to the minimum corner, None if the coordinates
+    /// are outside of the schematic.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate relative to the minimum corner.
+    /// * `y` - The y coordinate relative to the minimum corner.
+    /// * `z` - The z coordinate relative to the minimum corner.
+    pub fn get_block(&self, x: u16, y: u16, z: u16) -> Option<&str> {
+        if x >= self.width || y >= self.height || z >= self.length {
+            return None;
+        }
+        let index = x as usize + z as usize * self.width as usize + y as usize * self.width as usize * self.length as usize;
+        return self.blocks.get(index).and_then(|i| self.palette.get(*i as usize)).map(|s| s.as_str());
+    }
+
+    /// Returns the schematic as an NBT blob in the Sponge schematic format.
+    pub fn to_blob(&self) -> Blob {
+        let mut blob = Blob::named("Schematic");
+        let palette = self
+            .palette
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state.to_owned(), Value::Int(i as i32)))
+            .collect::<HashMap<_, _>>();
+        let mut block_data = Vec::new();
+        for id in &self.blocks {
+            let mut id = *id;
+            while id >= 0x80 {
+                block_data.push((id & 0x7F | 0x80) as u8 as i8);
+                id >>= 7;
+            }
+            block_data.push(id as u8 as i8);
+        }
+        let entries = vec![
+            ("Version", Value::Int(2)),
+            ("DataVersion", Value::Int(self.data_version)),
+            ("Width", Value::Short(self.width as i16)),
+            ("Height", Value::Short(self.height as i16)),
+            ("Length", Value::Short(self.length as i16)),
+            ("Offset", Value::IntArray(vec![self.offset.0, self.offset.1, self.offset.2])),
+            ("PaletteMax", Value::Int(self.palette.len() as i32)),
+            ("Palette", Value::Compound(palette)),
+            ("BlockData", Value::ByteArray(block_data)),
+            ("BlockEntities", Value::List(self.block_entities.iter().cloned().map(Value::Compound).collect())),
+        ];
+        for (name, value) in entries {
+            blob.insert(name, value).expect("Schematic NBT should be valid");
+        }
+        return blob;
+    }
+
+    /// Writes the schematic as a gzipped Sponge schematic, the form used by .schem files.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst` - Where to write the schematic.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::world::World;
+    /// use std::fs::File;
+    ///
+    /// let world = World::new("world/region");
+    /// let schematic = world.extract_schematic((0, 60, 0), (31, 90, 31)).unwrap();
+    /// schematic.write(&mut File::create("build.schem").unwrap()).unwrap();
+    /// ```
+    pub fn write<W: io::Write>(&self, dst: &mut W) -> nbt::Result<()> {
+        return self.to_blob().to_gzip_writer(dst);
+    }
+}
@@ -0,0 +1,295 @@
+use nbt::Value;
+
+use crate::{block::{self, Block}, chunk::{bit_length, ChunkError}};
+
+use std::{cmp, collections::HashMap};
+
+/// A 16x16x16 vertical slice of a Chunk. The section borrows its data from the Chunk it was taken from.
+#[derive(Clone, Debug)]
+pub struct Section<'a> {
+    /// The y index of the section, the section contains the blocks from y * 16 to y * 16 + 15.
+    pub y: i8,
+    /// The compound that the section was read from.
+    tag: &'a HashMap<String, Value>,
+    /// The DataVersion of the Chunk that the section belongs to, 0 if it is not known.
+    data_version: i32,
+}
+
+impl<'a> Section<'a> {
+    /// Returns a section from the compound found in a Chunk's section list.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The y index of the section.
+    /// * `tag` - The compound containing the section data.
+    /// * `data_version` - The DataVersion of the Chunk, 0 if it is not known.
+    pub(crate) fn new(y: i8, tag: &'a HashMap<String, Value>, data_version: i32) -> Section<'a> {
+        return Section { y, tag, data_version };
+    }
+
+    /// Returns the distinct blocks that appear in the section. The returned blocks have no coordinates or biome.
+    /// Palette entries that are not a compound with a name, which only happens in corrupt chunks, are returned as air.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// for section in chunk.top_sections(1) {
+    ///     for block in section.palette() {
+    ///         println!("{}", block);
+    ///     }
+    /// }
+    /// ```
+    pub fn palette(&self) -> Vec<Block> {
+        return match self.palette_tags() {
+            Some(palette) => palette
+                .iter()
+                .map(palette_block)
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    /// Returns true if the section contains nothing but air. Only the palette is looked at, so this is a cheap way
+    /// to skip sections before decoding their blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::region::Region;
+    /// let region = Region::from_file("r.0.0.mca".into()).unwrap();
+    /// let chunk = region.get_chunk(0, 0).unwrap();
+    /// let filled = chunk.sections().filter(|s| !s.is_empty()).count();
+    /// println!("{} sections with blocks", filled);
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return match self.palette_tags() {
+            Some(palette) => palette.iter().all(is_air),
+            None => true,
+        };
+    }
+
+    /// Returns the biome of each of the 64 4x4x4 cells of the section, in the same y, z, x order as the blocks. The
+    /// cell containing the local coordinates x, y, z is at index (y / 4) * 16 + (z / 4) * 4 + (x / 4). An empty
+    /// vector is returned if the section has no biomes. The same corrupt biomes that get_block rejects are a
+    /// ChunkError::InvalidTag here, a palette entry that is not a string or a cell that refers to an entry past the
+    /// end of the palette.
+    pub(crate) fn biome_cells(&self) -> Result<Vec<&'a str>, ChunkError> {
+        let biomes = if let Some(Value::Compound(b)) = self.tag.get("biomes") {
+            b
+        } else {
+            return Ok(Vec::new());
+        };
+        let palette = match biomes.get("palette") {
+            Some(Value::List(p)) if !p.is_empty() => p,
+            _ => return Ok(Vec::new()),
+        };
+        let names = palette
+            .iter()
+            .map(|b| if let Value::String(s) = b { Ok(s.as_str()) } else { Err(ChunkError::InvalidTag("palette")) })
+            .collect::<Result<Vec<&str>, ChunkError>>()?;
+        let indices = match biomes.get("data") {
+            Some(Value::LongArray(data)) => {
+                unpack_palette_indices(data, cmp::max(bit_length(palette.len() - 1), 1), 64)
+            }
+            _ => vec![0; 64],
+        };
+        return indices.iter().map(|i| names.get(*i).copied().ok_or(ChunkError::InvalidTag("data"))).collect();
+    }
+
+    /// Returns the light level stored for a block in one of the section's light arrays, None if the section does
+    /// not have the array. Light is stored as 4 bits per block in the same y, z, x order as the blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the light array, either 'SkyLight' or 'BlockLight'
+    /// * `x` - The x coordinate within the section (0-15)
+    /// * `y` - The y coordinate within the section (0-15)
+    /// * `z` - The z coordinate within the section (0-15)
+    pub(crate) fn light(&self, name: &str, x: i32, y: i32, z: i32) -> Option<u8> {
+        let light = if let Some(Value::ByteArray(l)) = self.tag.get(name) {
+            l
+        } else {
+            return None;
+        };
+        let index = (y * 256 + z * 16 + x) as usize;
+        return light.get(index / 2).map(|b| (*b as u8 >> (index % 2 * 4)) & 0xF);
+    }
+
+    /// Returns the raw palette list of the section, None if the section has no block states. Before 1.18 the
+    /// palette is kept directly in the section as 'Palette' rather than inside 'block_states'.
+    pub(crate) fn palette_tags(&self) -> Option<&'a Vec<Value>> {
+        let palette = match self.tag.get("block_states") {
+            Some(Value::Compound(bs)) => bs.get("palette"),
+            _ => self.tag.get("Palette"),
+        };
+        return if let Some(Value::List(p)) = palette {
+            Some(p)
+        } else {
+            None
+        };
+    }
+
+    /// Returns the palette entry for each of the 4096 blocks in the section, in the same y, z, x order used by
+    /// get_block. An empty vector is returned if the section has no block states, and ChunkError::InvalidTag if a
+    /// block refers to an entry past the end of the palette.
+    pub(crate) fn states(&self) -> Result<Vec<&'a Value>, ChunkError> {
+        return match self.checked_palette_indices()? {
+            Some((palette, indices)) => Ok(indices.iter().map(|i| &palette[*i]).collect()),
+            None => Ok(Vec::new()),
+        };
+    }
+
+    /// Returns each palette entry of the section along with the number of blocks in the section that use it. The
+    /// state array is only decoded once, so this is much cheaper than looking at each block. Entries that are not
+    /// used by any block are left out. ChunkError::InvalidTag is returned if a block refers to an entry past the end
+    /// of the palette.
+    pub(crate) fn state_counts(&self) -> Result<Vec<(&'a Value, u64)>, ChunkError> {
+        let (palette, indices) = match self.checked_palette_indices()? {
+            Some(p) => p,
+            None => return Ok(Vec::new()),
+        };
+        let mut counts = vec![0u64; palette.len()];
+        for i in indices {
+            counts[i] += 1;
+        }
+        return Ok(palette.iter().zip(counts).filter(|(_, count)| *count > 0).collect());
+    }
+
+    /// Returns the same as palette_indices, with ChunkError::InvalidTag in place of indices that are past the end of
+    /// the palette, which only happens in corrupt chunks. Every index returned is within the palette.
+    pub(crate) fn checked_palette_indices(&self) -> Result<Option<(&'a Vec<Value>, Vec<usize>)>, ChunkError> {
+        return match self.palette_indices() {
+            Some((palette, indices)) if indices.iter().any(|i| *i >= palette.len()) => Err(ChunkError::InvalidTag("data")),
+            result => Ok(result),
+        };
+    }
+
+    /// Returns the palette of the section along with the palette index of each of the 4096 blocks. None is
+    /// returned if the section has no block states.
+    pub(crate) fn palette_indices(&self) -> Option<(&'a Vec<Value>, Vec<usize>)> {
+        let palette = match self.palette_tags() {
+            Some(p) if !p.is_empty() => p,
+            _ => return None,
+        };
+        let data = match self.tag.get("block_states") {
+            Some(Value::Compound(bs)) => bs.get("data"),
+            _ => self.tag.get("BlockStates"),
+        };
+        let indices = match data {
+            Some(Value::LongArray(data)) => {
+                let bits = cmp::max(bit_length(palette.len() - 1), 4);
+                // Before 20w17a (1.16) indices were packed across the boundaries between longs. Chunks without a
+                // DataVersion are told apart by the length of the array, which is shorter when indices span longs.
+                let spanning = if self.data_version > 0 {
+                    self.data_version < PADDED_STATES_DATA_VERSION
+                } else {
+                    data.len() == 4096 * bits as usize / 64 && data.len() != (4096 + 64 / bits as usize - 1) / (64 / bits as usize)
+                };
+                if spanning {
+                    unpack_spanning_indices(data, bits, 4096)
+                } else {
+                    unpack_palette_indices(data, bits, 4096)
+                }
+            }
+            _ => vec![0; 4096],
+        };
+        return Some((palette, indices));
+    }
+
+    /// Returns true if both sections contain the same blocks. A missing section is considered to be all air.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first section, if present.
+    /// * `b` - The second section, if present.
+    pub(crate) fn blocks_equal(a: Option<&Section>, b: Option<&Section>) -> bool {
+        if let (Some(a), Some(b)) = (a, b) {
+            if a.tag.get("block_states").is_some() && a.tag.get("block_states") == b.tag.get("block_states") {
+                return true;
+            }
+        }
+        // Sections that cannot be decoded are only equal if their block states are identical, checked above.
+        let (a_states, b_states) = match (a.map(|s| s.states()).transpose(), b.map(|s| s.states()).transpose()) {
+            (Ok(a_states), Ok(b_states)) => (a_states.unwrap_or_default(), b_states.unwrap_or_default()),
+            _ => return false,
+        };
+        return match (a_states.is_empty(), b_states.is_empty()) {
+            (true, true) => true,
+            (false, true) => a_states.into_iter().all(is_air),
+            (true, false) => b_states.into_iter().all(is_air),
+            (false, false) => a_states == b_states,
+        };
+    }
+}
+
+/// The DataVersion of 20w17a, the first version in which block state indices never span two longs.
+const PADDED_STATES_DATA_VERSION: i32 = 2529;
+
+/// Unpacks palette indices from a packed long array. Each index is `bits` wide and indices never span two longs.
+///
+/// # Arguments
+///
+/// * `data` - The packed long array.
+/// * `bits` - The number of bits used by each index.
+/// * `count` - The number of indices to unpack.
+fn unpack_palette_indices(data: &[i64], bits: u32, count: usize) -> Vec<usize> {
+    let per_long = 64 / bits as usize;
+    let mask = (1u64 << bits) - 1;
+    let mut indices = Vec::with_capacity(count);
+    for n in 0..count {
+        let long = data.get(n / per_long).map_or(0, |l| *l as u64);
+        indices.push(((long >> (n % per_long * bits as usize)) & mask) as usize);
+    }
+    return indices;
+}
+
+/// Unpacks palette indices from a packed long array where indices can span two longs, the layout used before 1.16.
+///
+/// # Arguments
+///
+/// * `data` - The packed long array.
+/// * `bits` - The number of bits used by each index.
+/// * `count` - The number of indices to unpack.
+fn unpack_spanning_indices(data: &[i64], bits: u32, count: usize) -> Vec<usize> {
+    let mask = (1u64 << bits) - 1;
+    let mut indices = Vec::with_capacity(count);
+    for n in 0..count {
+        let start = n * bits as usize;
+        let low = data.get(start / 64).map_or(0, |l| *l as u64) >> (start % 64);
+        let high = if start % 64 + bits as usize > 64 {
+            data.get(start / 64 + 1).map_or(0, |l| *l as u64) << (64 - start % 64)
+        } else {
+            0
+        };
+        indices.push(((low | high) & mask) as usize);
+    }
+    return indices;
+}
+
+/// Returns the block for a palette entry, without coordinates or biome. Entries that are not a compound with a name
+/// are read as air instead of panicking.
+///
+/// # Arguments
+///
+/// * `tag` - The palette entry.
+pub(crate) fn palette_block(tag: &Value) -> Block {
+    return Block::from_palette(tag, None, block::palette_properties(tag), None)
+        .unwrap_or_else(|_| Block::from_name(String::from("minecraft:air"), None, None, None));
+}
+
+/// Returns true if a palette entry is one of the air blocks.
+///
+/// # Arguments
+///
+/// * `tag` - The palette entry.
+pub(crate) fn is_air(tag: &Value) -> bool {
+    if let Value::Compound(c) = tag {
+        if let Some(Value::String(name)) = c.get("Name") {
+            return matches!(name.as_str(), "minecraft:air" | "minecraft:cave_air" | "minecraft:void_air");
+        }
+    }
+    return false;
+}
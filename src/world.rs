@@ -0,0 +1,289 @@
+use nbt::Value;
+
+use crate::{block, coords, region::Region, schematic::Schematic};
+
+use std::{
+    cmp,
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The most blocks a schematic can be extracted with, 2^28 blocks take up 1 GiB. Larger boxes should be split up.
+const MAX_SCHEMATIC_BLOCKS: u64 = 1 << 28;
+
+/// A Minecraft world, read from the directory holding its region files. Region files are only opened when
+/// something inside of them is needed.
+#[derive(Clone, Debug)]
+pub struct World {
+    /// The directory containing the region files, ie. 'world/region'
+    pub path: PathBuf,
+}
+
+impl World {
+    /// Returns a world whose region files are in the given directory. Nothing is read until it is needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory containing the region files, ie. 'world/region'
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::world::World;
+    ///
+    /// let world = World::new("world/region");
+    /// ```
+    pub fn new<P: AsRef<Path>>(path: P) -> World {
+        return World { path: path.as_ref().to_path_buf() };
+    }
+
+    /// Returns a schematic of every block in a box of the world, along with the block entities in the box. The
+    /// corners are world block coordinates and are both included in the box. The box can span any number of
+    /// chunks and regions, each of which is only read once. Blocks in chunks or regions that do not exist are
+    /// air. An error is returned if a region file exists but cannot be opened, if a section has blocks that refer to
+    /// entries past the end of its palette, or if the box is more than 65535 blocks along any axis, which is the
+    /// largest size a schematic can hold. Boxes of more than 2^28 blocks are also rejected, as they would need
+    /// more than 1 GiB of memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - One corner of the box.
+    /// * `max` - The opposite corner of the box.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use simple_anvil::world::World;
+    ///
+    /// let world = World::new("world/region");
+    /// let schematic = world.extract_schematic((-20, 60, -20), (20, 100, 20)).unwrap();
+    /// println!("{} distinct blocks", schematic.palette.len());
+    /// ```
+    pub fn extract_schematic(&self, min: (i32, i32, i32), max: (i32, i32, i32)) -> io::Result<Schematic> {
+        let (min, max) = (
+            (cmp::min(min.0, max.0), cmp::min(min.1, max.1), cmp::min(min.2, max.2)),
+            (cmp::max(min.0, max.0), cmp::max(min.1, max.1), cmp::max(min.2, max.2)),
+        );
+        let size = |low: i32, high: i32| high as i64 - low as i64 + 1;
+        let (width, height, length) = (size(min.0, max.0), size(min.1, max.1), size(min.2, max.2));
+        if width > u16::MAX as i64 || height > u16::MAX as i64 || length > u16::MAX as i64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("a schematic can be at most 65535 blocks along each axis, not {}x{}x{}", width, height, length),
+            ));
+        }
+        let volume = (width as u64).checked_mul(height as u64).and_then(|area| area.checked_mul(length as u64));
+        if !matches!(volume, Some(volume) if volume <= MAX_SCHEMATIC_BLOCKS) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("a {}x{}x{} box holds more than {} blocks", width, height, length, MAX_SCHEMATIC_BLOCKS),
+            ));
+        }
+        let (width, height, length) = (width as usize, height as usize, length as usize);
+
+        let mut palette = vec![String::from("minecraft:air")];
+        let mut ids = HashMap::from([(String::from("minecraft:air"), 0u32)]);
+        let mut blocks = vec![0u32; width * height * length];
+        let mut block_entities = Vec::new();
+        let mut data_version = 0;
+
+        let (min_chunk_x, min_chunk_z) = coords::block_to_chunk(min.0, min.2);
+        let (max_chunk_x, max_chunk_z) = coords::block_to_chunk(max.0, max.2);
+        let (min_region_x, min_region_z) = coords::chunk_to_region(min_chunk_x, min_chunk_z);
+        let (max_region_x, max_region_z) = coords::chunk_to_region(max_chunk_x, max_chunk_z);
+        for region_x in min_region_x..=max_region_x {
+            for region_z in min_region_z..=max_region_z {
+                let region = match Region::open_lazy(self.path.join(format!("r.{}.{}.mca", region_x, region_z))) {
+                    Ok(r) => r,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e),
+                };
+                let chunks_x = cmp::max(min_chunk_x, region_x * 32)..=cmp::min(max_chunk_x, region_x * 32 + 31);
+                for chunk_x in chunks_x {
+                    let chunks_z = cmp::max(min_chunk_z, region_z * 32)..=cmp::min(max_chunk_z, region_z * 32 + 31);
+                    for chunk_z in chunks_z {
+                        let chunk = match region.get_chunk((chunk_x - region_x * 32) as u32, (chunk_z - region_z * 32) as u32) {
+                            Some(c) => c,
+                            None => continue,
+                        };
+                        if data_version == 0 {
+                            data_version = chunk.get_data_version();
+                        }
+
+                        for section in chunk.section_list() {
+                            let section_y = section.y as i32 * 16;
+                            if section_y + 15 < min.1 || section_y > max.1 {
+                                continue;
+                            }
+                            let checked = section
+                                .checked_palette_indices()
+                                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                            let (section_palette, indices) = match checked {
+                                Some(p) => p,
+                                None => continue,
+                            };
+                            let section_ids = section_palette
+                                .iter()
+                                .map(|tag| {
+                                    let state = block_state(tag);
+                                    let next = ids.len() as u32;
+                                    let id = *ids.entry(state.clone()).or_insert(next);
+                                    if id == next {
+                                        palette.push(state);
+                                    }
+                                    id
+                                })
+                                .collect::<Vec<u32>>();
+
+                            for y in cmp::max(section_y, min.1)..=cmp::min(section_y + 15, max.1) {
+                                for z in cmp::max(chunk_z * 16, min.2)..=cmp::min(chunk_z * 16 + 15, max.2) {
+                                    for x in cmp::max(chunk_x * 16, min.0)..=cmp::min(chunk_x * 16 + 15, max.0) {
+                                        let index = ((y - section_y) * 256 + z.rem_euclid(16) * 16 + x.rem_euclid(16)) as usize;
+                                        let id = section_ids[indices[index]];
+                                        let (sx, sy, sz) = ((x - min.0) as usize, (y - min.1) as usize, (z - min.2) as usize);
+                                        blocks[sx + sz * width + sy * width * length] = id;
+                                    }
+                                }
+                            }
+                        }
+
+                        for entity in chunk.raw_block_entities() {
+                            let entity = if let Value::Compound(e) = entity {
+                                e
+                            } else {
+                                continue;
+                            };
+                            let (x, y, z) = match (entity.get("x"), entity.get("y"), entity.get("z")) {
+                                (Some(Value::Int(x)), Some(Value::Int(y)), Some(Value::Int(z))) => (*x, *y, *z),
+                                _ => continue,
+                            };
+                            if x < min.0 || x > max.0 || y < min.1 || y > max.1 || z < min.2 || z > max.2 {
+                                continue;
+                            }
+                            let mut converted = entity
+                                .iter()
+                                .filter(|(k, _)| !matches!(k.as_str(), "x" | "y" | "z" | "id" | "keepPacked"))
+                                .map(|(k, v)| (k.to_owned(), v.clone()))
+                                .collect::<HashMap<String, Value>>();
+                            converted.insert(String::from("Pos"), Value::IntArray(vec![x - min.0, y - min.1, z - min.2]));
+                            if let Some(id) = entity.get("id") {
+                                converted.insert(String::from("Id"), id.clone());
+                            }
+                            block_entities.push(converted);
+                        }
+                    }
+                }
+            }
+        }
+
+        return Ok(Schematic {
+            width: width as u16,
+            height: height as u16,
+            length: length as u16,
+            offset: min,
+            data_version,
+            palette,
+            blocks,
+            block_entities,
+        });
+    }
+}
+
+/// Returns the block state string of a palette entry as used by schematics, the name followed by the properties
+/// in square brackets, ie. 'minecraft:oak_stairs[facing=east,half=bottom]'. Properties are sorted by name so that
+/// the same state always gives the same string.
+///
+/// # Arguments
+///
+/// * `tag` - The palette entry.
+fn block_state(tag: &Value) -> String {
+    let name = match tag {
+        Value::Compound(c) => match c.get("Name") {
+            Some(Value::String(n)) => n.to_owned(),
+            _ => String::from("minecraft:air"),
+        },
+        _ => String::from("minecraft:air"),
+    };
+    let mut properties = block::palette_properties(tag).unwrap_or_default();
+    if properties.is_empty() {
+        return name;
+    }
+    properties.sort();
+    let properties = properties.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>();
+    return format!("{}[{}]", name, properties.join(","));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{block::Block, region::RegionBuilder};
+
+    use std::fs;
+
+    /// Returns a world in a new temporary directory named after the test.
+    fn temp_world(name: &str) -> World {
+        let directory = std::env::temp_dir().join(format!("simple-anvil-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        return World::new(directory);
+    }
+
+    #[test]
+    fn extract_schematic_skips_missing_regions() {
+        let world = temp_world("schematic-missing");
+        let stone = Block::from_name("minecraft:stone".into(), None, None, None);
+        let region = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 1, 64, 2, &stone).to_bytes();
+        fs::write(world.path.join("r.0.0.mca"), region).unwrap();
+        // The box reaches into r.-1.0.mca, which does not exist.
+        let schematic = world.extract_schematic((-2, 64, 0), (2, 64, 2)).unwrap();
+        assert_eq!(schematic.get_block(3, 0, 2), Some("minecraft:stone"));
+        assert_eq!(schematic.get_block(0, 0, 2), Some("minecraft:air"));
+        fs::remove_dir_all(&world.path).unwrap();
+    }
+
+    #[test]
+    fn extract_schematic_returns_errors_from_unreadable_regions() {
+        let world = temp_world("schematic-unreadable");
+        fs::write(world.path.join("r.0.0.mca"), [0; 10]).unwrap();
+        let error = world.extract_schematic((0, 0, 0), (1, 1, 1)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        fs::remove_dir_all(&world.path).unwrap();
+    }
+
+    #[test]
+    fn extract_schematic_returns_errors_from_corrupt_sections() {
+        let world = temp_world("schematic-corrupt");
+        let stone = Block::from_name("minecraft:stone".into(), None, None, None);
+        let mut chunk = RegionBuilder::new("r.0.0.mca".into()).set_block(0, 0, 0, 0, 0, &stone).build().get_chunk(0, 0).unwrap();
+        // Two palette entries use 4 bits each, so the first block can point at entry 5 which does not exist.
+        let mut data = vec![0i64; 256];
+        data[0] = 5;
+        let mut block_states = HashMap::new();
+        block_states.insert("palette".to_string(), Value::List(vec![Value::Compound(HashMap::new()); 2]));
+        block_states.insert("data".to_string(), Value::LongArray(data));
+        let mut section = HashMap::new();
+        section.insert("Y".to_string(), Value::Byte(0));
+        section.insert("block_states".to_string(), Value::Compound(block_states));
+        chunk.data.insert("sections", Value::List(vec![Value::Compound(section)])).unwrap();
+        let region = RegionBuilder::new("r.0.0.mca".into()).chunk_nbt(0, 0, *chunk.data).to_bytes();
+        fs::write(world.path.join("r.0.0.mca"), region).unwrap();
+        let error = world.extract_schematic((0, 0, 0), (1, 1, 1)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        fs::remove_dir_all(&world.path).unwrap();
+    }
+
+    #[test]
+    fn extract_schematic_rejects_boxes_too_large_for_a_schematic() {
+        let world = World::new("does-not-exist");
+        let error = world.extract_schematic((0, 0, 0), (65535, 0, 0)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(world.extract_schematic((0, 0, 0), (65534, 0, 0)).is_ok());
+        assert!(world.extract_schematic((i32::MIN, 0, 0), (i32::MAX, 0, 0)).is_err());
+        let error = world.extract_schematic((0, 0, 0), (65534, 383, 65534)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let error = world.extract_schematic((0, 0, 0), (1024, 255, 1023)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}